@@ -18,7 +18,14 @@
 //!
 //! [uefi_cc]: <https://uefi.org/specs/UEFI/2.10/02_Overview.html#calling-conventions>
 //! [uefi_dt]: <https://uefi.org/specs/UEFI/2.10/02_Overview.html#common-uefi-data-types>
-use core::{ffi::c_void, fmt, ptr::null_mut};
+use alloc::{string::String, vec::Vec};
+use core::{
+    ffi::c_void,
+    fmt,
+    iter::once,
+    ops::{BitAnd, BitOr, Not},
+    ptr::{null_mut, NonNull},
+};
 
 use nuuid::Uuid;
 
@@ -101,6 +108,33 @@ impl fmt::Display for Boolean {
     }
 }
 
+impl Not for Boolean {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        (!self.to_bool()).into()
+    }
+}
+
+impl BitAnd for Boolean {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        (self.to_bool() & rhs.to_bool()).into()
+    }
+}
+
+impl BitOr for Boolean {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        (self.to_bool() | rhs.to_bool()).into()
+    }
+}
+
 /// A 1-byte UEFI character, ASCII Latin-1 unless specified otherwise.
 pub type Char8 = u8;
 
@@ -108,6 +142,22 @@ pub type Char8 = u8;
 /// 2.1 and ISO/IEC 10646 standards unless specified otherwise.
 pub type Char16 = u16;
 
+/// Decode a UCS-2/UTF-16 string, without a trailing nul, into a [`String`]
+///
+/// Invalid characters are mapped to [`char::REPLACEMENT_CHARACTER`]
+pub fn decode_ucs2(s: &[Char16]) -> String {
+    char::decode_utf16(s.iter().copied())
+        .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Encode `s` as a nul terminated UCS-2/UTF-16 string
+///
+/// Note that `s` may contain internal nuls.
+pub fn encode_ucs2(s: &str) -> Vec<Char16> {
+    s.encode_utf16().chain(once(0)).collect()
+}
+
 /// UEFI Globally Unique Identifier, or GUID.
 ///
 /// This is FFI compatible with and ABI Identical to a 128-bit buffer thats
@@ -121,7 +171,9 @@ pub type Char16 = u16;
 ///
 /// [rfc4122]: <https://www.rfc-editor.org/rfc/rfc4122>
 /// [aa]: <https://uefi.org/specs/UEFI/2.10/Apx_A_GUID_and_Time_Formats.html>
-#[derive(Clone, Copy, PartialEq, Eq)]
+///
+/// GUIDs are ordered by their raw bytes, suitable for use as map keys.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[repr(C, align(8))]
 pub struct Guid([u8; 16]);
 
@@ -137,11 +189,31 @@ impl Guid {
     pub const fn to_bytes(self) -> [u8; 16] {
         self.0
     }
+
+    /// Create a new [`Guid`] from a [`Uuid`]
+    ///
+    /// A GUID stores its first three fields in little endian,
+    /// while a [`Uuid`] is entirely big endian,
+    /// so the bytes of `uuid` are swapped to match.
+    ///
+    /// Both will display as the same string.
+    #[inline]
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid.to_bytes_me())
+    }
+
+    /// This [`Guid`] as a [`Uuid`]
+    ///
+    /// See [`Guid::from_uuid`] for details on the byte order.
+    #[inline]
+    pub fn to_uuid(self) -> Uuid {
+        Uuid::from_bytes_me(self.0)
+    }
 }
 
 impl fmt::Debug for Guid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let uuid = Uuid::from_bytes_me(self.0);
+        let uuid = self.to_uuid();
         f.debug_tuple("Guid") //.
             .field(&self.0)
             .field(&format_args!("[Guid] {uuid}"))
@@ -151,8 +223,7 @@ impl fmt::Debug for Guid {
 
 impl fmt::Display for Guid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let uuid = Uuid::from_bytes_me(self.0);
-        uuid.fmt(f)
+        self.to_uuid().fmt(f)
     }
 }
 
@@ -211,6 +282,31 @@ impl Status {
     pub const fn is_oem(self) -> bool {
         self.0 & NEXT_BIT != 0
     }
+
+    /// Create an OEM error [`Status`] with code `n`
+    ///
+    /// The high bits of `n` are ignored.
+    #[inline]
+    pub const fn oem_error(n: usize) -> Self {
+        Self(ERROR_BIT | NEXT_BIT | (n & !(ERROR_BIT | NEXT_BIT)))
+    }
+
+    /// Create an OEM warning [`Status`] with code `n`
+    ///
+    /// The high bits of `n` are ignored.
+    #[inline]
+    pub const fn oem_warning(n: usize) -> Self {
+        Self(NEXT_BIT | (n & !(ERROR_BIT | NEXT_BIT)))
+    }
+
+    /// Create a UEFI warning [`Status`] with code `n`
+    ///
+    /// The high bits of `n` are ignored.
+    /// Note that a code of `0` is [`Status::SUCCESS`].
+    #[inline]
+    pub const fn warning(n: usize) -> Self {
+        Self(n & !(ERROR_BIT | NEXT_BIT))
+    }
 }
 
 impl Status {
@@ -382,6 +478,20 @@ impl Handle {
         Self(p)
     }
 
+    /// Create a new [`Handle`], or [`None`] if `p` is null
+    ///
+    /// # Safety
+    ///
+    /// If `p` is not null, the same requirements as [`Handle::new`] apply.
+    #[inline]
+    pub unsafe fn new_checked(p: *mut c_void) -> Option<Self> {
+        if p.is_null() {
+            None
+        } else {
+            Some(Self(p))
+        }
+    }
+
     /// Create a new null [`Handle`]
     ///
     /// This is safe because a null [`Handle`] is an error, and
@@ -396,6 +506,13 @@ impl Handle {
     pub const fn as_ptr(self) -> *mut c_void {
         self.0
     }
+
+    /// Get the pointer for this [`Handle`] as a [`NonNull`],
+    /// or [`None`] if it is null
+    #[inline]
+    pub fn as_non_null(self) -> Option<NonNull<c_void>> {
+        NonNull::new(self.0)
+    }
 }
 
 /// An opaque handle to a UEFI event
@@ -407,6 +524,19 @@ impl Handle {
 pub struct Event(*mut c_void);
 
 impl Event {
+    /// Create a new [`Event`]
+    ///
+    /// # Safety
+    ///
+    /// By calling this, you assert that `p` actually does
+    /// point to a legitimate UEFI event.
+    ///
+    /// See [`Handle::new`] for details.
+    #[inline]
+    pub const unsafe fn new(p: *mut c_void) -> Self {
+        Self(p)
+    }
+
     /// Get the pointer for this [`Event`]
     #[inline]
     pub const fn as_ptr(self) -> *mut c_void {
@@ -428,19 +558,160 @@ pub struct LogicalBlockAddress(u64);
 #[repr(transparent)]
 pub struct TaskPriorityLevel(usize);
 
+impl TaskPriorityLevel {
+    /// `TPL_APPLICATION`, the level applications normally run at
+    pub const APPLICATION: Self = Self(4);
+
+    /// `TPL_CALLBACK`, for most event notification functions
+    pub const CALLBACK: Self = Self(8);
+
+    /// `TPL_NOTIFY`, for blocking I/O notifications
+    pub const NOTIFY: Self = Self(16);
+
+    /// `TPL_HIGH_LEVEL`, interrupts are disabled
+    pub const HIGH_LEVEL: Self = Self(31);
+}
+
 /// 32-byte buffer containing a MAC address
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct MacAddress([u8; 32]);
 
+impl MacAddress {
+    /// The raw 32-byte buffer
+    ///
+    /// Only the first few bytes are meaningful, depending on the
+    /// type of network interface.
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The first 6 bytes, a standard Ethernet EUI-48 address
+    #[inline]
+    pub const fn as_eui48(&self) -> [u8; 6] {
+        let b = &self.0;
+        [b[0], b[1], b[2], b[3], b[4], b[5]]
+    }
+}
+
+/// The EUI-48 address, such as `aa:bb:cc:dd:ee:ff`
+///
+/// See [`MacAddress::as_eui48`]
+impl fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.as_eui48();
+        write!(f, "{a:02x}:{b:02x}:{c:02x}:{d:02x}:{e:02x}:{g:02x}")
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct IPV4([u8; 4]);
 
+impl IPV4 {
+    /// Create a new [`IPV4`] address from its `octets`
+    #[inline]
+    pub const fn new(octets: [u8; 4]) -> Self {
+        Self(octets)
+    }
+
+    /// The octets of this address
+    #[inline]
+    pub const fn octets(self) -> [u8; 4] {
+        self.0
+    }
+}
+
+impl From<[u8; 4]> for IPV4 {
+    #[inline]
+    fn from(octets: [u8; 4]) -> Self {
+        Self::new(octets)
+    }
+}
+
+/// Dotted-quad notation, such as `192.168.0.1`
+impl fmt::Display for IPV4 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d] = self.0;
+        write!(f, "{a}.{b}.{c}.{d}")
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct IPV6([u8; 16]);
 
+impl IPV6 {
+    /// Create a new [`IPV6`] address from its `octets`
+    #[inline]
+    pub const fn new(octets: [u8; 16]) -> Self {
+        Self(octets)
+    }
+
+    /// The octets of this address
+    #[inline]
+    pub const fn octets(self) -> [u8; 16] {
+        self.0
+    }
+
+    /// The eight big endian 16-bit segments of this address
+    #[inline]
+    pub fn segments(self) -> [u16; 8] {
+        let mut seg = [0u16; 8];
+        for (s, b) in seg.iter_mut().zip(self.0.chunks_exact(2)) {
+            *s = u16::from_be_bytes([b[0], b[1]]);
+        }
+        seg
+    }
+}
+
+impl From<[u8; 16]> for IPV6 {
+    #[inline]
+    fn from(octets: [u8; 16]) -> Self {
+        Self::new(octets)
+    }
+}
+
+/// Colon-hex notation, such as `fe80::1`
+///
+/// The longest run of two or more zero segments is compressed to `::`,
+/// as recommended by RFC 5952.
+impl fmt::Display for IPV6 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seg = self.segments();
+
+        // (start, len) of the longest run of zero segments
+        let mut zeros = (0, 0);
+        let mut i = 0;
+        while i < seg.len() {
+            let len = seg[i..].iter().take_while(|s| **s == 0).count();
+            if len > zeros.1 {
+                zeros = (i, len);
+            }
+            i += len.max(1);
+        }
+
+        let write = |f: &mut fmt::Formatter<'_>, seg: &[u16]| {
+            for (i, s) in seg.iter().enumerate() {
+                if i != 0 {
+                    f.write_str(":")?;
+                }
+                write!(f, "{s:x}")?;
+            }
+            Ok(())
+        };
+
+        if zeros.1 < 2 {
+            return write(f, &seg);
+        }
+        let (start, len) = zeros;
+        write(f, &seg[..start])?;
+        f.write_str("::")?;
+        write(f, &seg[start + len..])
+    }
+}
+
 /// An [`IPV4`] or [`IPV6`] address
 ///
 /// A 16-byte buffer aligned on 4 bytes
@@ -449,6 +720,39 @@ pub struct IPV6([u8; 16]);
 pub struct IP([u8; 16]);
 
 impl IP {
+    /// Create a new [`IP`] from an [`IPV4`] address
+    #[inline]
+    pub const fn from_ipv4(ip: IPV4) -> Self {
+        let o = ip.octets();
+        Self([o[0], o[1], o[2], o[3], 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])
+    }
+
+    /// Create a new [`IP`] from an [`IPV6`] address
+    #[inline]
+    pub const fn from_ipv6(ip: IPV6) -> Self {
+        Self(ip.octets())
+    }
+
+    /// Interpret this as an [`IPV4`] address
+    ///
+    /// This uses the first 4 bytes of the buffer.
+    #[inline]
+    pub const fn ipv4(&self) -> IPV4 {
+        IPV4([self.0[0], self.0[1], self.0[2], self.0[3]])
+    }
+
+    /// Interpret this as an [`IPV6`] address
+    #[inline]
+    pub const fn ipv6(&self) -> IPV6 {
+        IPV6(self.0)
+    }
+
+    /// The raw 16-byte buffer
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
     /// Get a pointer to the aligned buffer
     #[inline]
     pub const fn as_ptr(&self) -> *const u8 {
@@ -461,3 +765,73 @@ impl IP {
         self.0.as_mut_ptr()
     }
 }
+
+impl From<IPV4> for IP {
+    #[inline]
+    fn from(ip: IPV4) -> Self {
+        Self::from_ipv4(ip)
+    }
+}
+
+impl From<IPV6> for IP {
+    #[inline]
+    fn from(ip: IPV6) -> Self {
+        Self::from_ipv6(ip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn ip_display() {
+        assert_eq!(IPV4::new([192, 168, 0, 1]).to_string(), "192.168.0.1");
+
+        let mut v6 = [0u8; 16];
+        assert_eq!(IPV6::new(v6).to_string(), "::");
+        v6[15] = 1;
+        assert_eq!(IPV6::new(v6).to_string(), "::1");
+        v6[0] = 0xFE;
+        v6[1] = 0x80;
+        assert_eq!(IPV6::new(v6).to_string(), "fe80::1");
+        // Single zero segments are not compressed
+        let v6 = [0, 1, 0, 0, 0, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0, 7];
+        assert_eq!(IPV6::new(v6).to_string(), "1:0:2:3:4:5:6:7");
+    }
+
+    #[test]
+    fn guid_uuid() {
+        let bytes = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+        let guid = Guid::new(bytes);
+        let uuid = guid.to_uuid();
+        assert_eq!(
+            uuid.to_bytes(),
+            [4, 3, 2, 1, 6, 5, 8, 7, 9, 10, 11, 12, 13, 14, 15, 16]
+        );
+        assert_eq!(Guid::from_uuid(uuid), guid);
+        assert_eq!(guid.to_string(), uuid.to_string());
+    }
+
+    #[test]
+    fn boolean_ops() {
+        // Any non-zero value is true
+        let weird = Boolean(2);
+        assert_eq!(!weird, Boolean::from(false));
+        assert_eq!(weird & Boolean(1), Boolean::from(true));
+        assert_eq!(weird & Boolean(0), Boolean::from(false));
+        assert_eq!(Boolean(0) | weird, Boolean::from(true));
+        assert_eq!(!Boolean(0), Boolean::from(true));
+    }
+
+    #[test]
+    fn mac_display() {
+        let mut mac = [0xFFu8; 32];
+        mac[..6].copy_from_slice(&[0xAA, 0xBB, 0x0C, 0xDD, 0xEE, 0x01]);
+        let mac = MacAddress(mac);
+        assert_eq!(mac.as_eui48(), [0xAA, 0xBB, 0x0C, 0xDD, 0xEE, 0x01]);
+        assert_eq!(mac.to_string(), "aa:bb:0c:dd:ee:01");
+    }
+}
@@ -47,6 +47,64 @@ impl UefiError {
     }
 }
 
+impl Status {
+    /// Returns [`Ok`] with the result of `f` if this is [`Status::SUCCESS`],
+    /// otherwise [`Err`].
+    ///
+    /// Like the [`From`] conversion, warnings are treated as errors.
+    ///
+    /// `f` is only called on success.
+    #[inline]
+    pub fn map_success<T, F: FnOnce() -> T>(self, f: F) -> Result<T> {
+        if self.is_success() {
+            Ok(f())
+        } else {
+            Err(UefiError::new(self))
+        }
+    }
+
+    /// Returns [`Ok`] with `value` if this is [`Status::SUCCESS`],
+    /// otherwise [`Err`].
+    ///
+    /// Like the [`From`] conversion, warnings are treated as errors.
+    ///
+    /// See [`Status::map_success`] to only compute the value on success.
+    #[inline]
+    pub fn into_result_with<T>(self, value: T) -> Result<T> {
+        self.map_success(|| value)
+    }
+
+    /// Classify this [`Status`] as success, warning, or error
+    ///
+    /// Unlike the [`From`] conversion to [`Result`],
+    /// this keeps warnings distinct from errors.
+    #[inline]
+    pub const fn outcome(self) -> Outcome {
+        if self.is_success() {
+            Outcome::Success
+        } else if self.is_warning() {
+            Outcome::Warning(self)
+        } else {
+            Outcome::Error(self)
+        }
+    }
+}
+
+/// The outcome of a [`Status`], distinguishing warnings from errors
+///
+/// See [`Status::outcome`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// [`Status::SUCCESS`]
+    Success,
+
+    /// The operation completed, but with a warning
+    Warning(Status),
+
+    /// The operation failed
+    Error(Status),
+}
+
 impl From<Status> for Result<()> {
     #[inline]
     fn from(value: Status) -> Self {
@@ -75,6 +133,20 @@ impl From<Status> for UefiError {
 /// All [`core::fmt::Write`] failures are treated as
 /// [`Status::DEVICE_ERROR`][sdr]
 ///
+/// This allows using `?` on [`write!`] in functions returning [`Result`]
+///
+/// ```rust
+/// # use nuefi_core::error::Result;
+/// use core::fmt::Write;
+///
+/// fn greet(out: &mut impl Write) -> Result<()> {
+///     write!(out, "Hello")?;
+///     Ok(())
+/// }
+/// # let mut s = String::new();
+/// # greet(&mut s).unwrap();
+/// ```
+///
 /// [sdr]: crate::base::Status::DEVICE_ERROR
 impl From<core::fmt::Error> for UefiError {
     #[inline]
@@ -92,8 +164,8 @@ impl core::fmt::Display for UefiError {
 impl core::fmt::Debug for UefiError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("UefiError")
-            .field("inner", &self.inner)
-            .field("[Display]", &format_args!("{}", self.inner))
+            .field("code", &format_args!("{:#X}", self.inner.code()))
+            .field("status", &format_args!("\"{}\"", self.inner))
             .finish()
     }
 }
@@ -107,6 +179,7 @@ mod imp {
     }
 
     impl<T> Sealed for core::result::Result<Option<T>, UefiError> {}
+    impl Sealed for core::result::Result<(), UefiError> {}
 }
 
 /// Helpful trait to work with [`Result<Option<T>>`]
@@ -147,3 +220,79 @@ impl<T> ResultOptExt<T> for core::result::Result<Option<T>, UefiError> {
         }
     }
 }
+
+/// Helpful trait to transform the [`Status`] of a [`Result<()>`]
+///
+/// This is only implemented for [`Result<()>`],
+/// as it holds no information other than the [`Status`].
+///
+/// This trait is sealed
+pub trait ResultExt: imp::Sealed {
+    /// Transform the [`Status`] of this [`Result`] with `f`
+    ///
+    /// `f` is called with [`Status::SUCCESS`] for [`Ok`],
+    /// and if it returns [`Status::SUCCESS`] the result is [`Ok`].
+    ///
+    /// This is useful to uniformly promote a warning to success,
+    /// or demote an expected error.
+    fn map_status<F: FnOnce(Status) -> Status>(self, f: F) -> Self;
+
+    /// The [`Status`] of this [`Result`], from either variant
+    fn and_status(self) -> Status;
+}
+
+impl ResultExt for Result<()> {
+    #[inline]
+    fn map_status<F: FnOnce(Status) -> Status>(self, f: F) -> Self {
+        f(self.and_status()).into()
+    }
+
+    #[inline]
+    fn and_status(self) -> Status {
+        match self {
+            Ok(()) => Status::SUCCESS,
+            Err(e) => e.status(),
+        }
+    }
+}
+
+/// Return types supported by the `entry` function
+///
+/// This is implemented for [`Status`],
+/// and [`Result<(), E>`][core::result::Result] for any `E` convertible to
+/// [`UefiError`].
+pub trait IntoEfiMain {
+    /// Convert this return value to our [`Result`]
+    fn into_efi_main(self) -> Result<()>;
+}
+
+impl<E: Into<UefiError>> IntoEfiMain for core::result::Result<(), E> {
+    #[inline]
+    fn into_efi_main(self) -> Result<()> {
+        self.map_err(Into::into)
+    }
+}
+
+impl IntoEfiMain for Status {
+    #[inline]
+    fn into_efi_main(self) -> Result<()> {
+        self.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+
+    use super::*;
+
+    #[test]
+    fn error_debug() {
+        let e = UefiError::new(Status::INVALID_PARAMETER);
+        let code = Status::INVALID_PARAMETER.code();
+        assert_eq!(
+            format!("{e:?}"),
+            format!("UefiError {{ code: {code:#X}, status: \"invalid parameter\" }}")
+        );
+    }
+}
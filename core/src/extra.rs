@@ -38,6 +38,33 @@ pub unsafe trait Entity {
     }
 }
 
+/// Object safe access to the GUID and name of an [`Entity`]
+///
+/// [`Entity`] itself can't be used as `dyn Entity`, because of its
+/// associated constants.
+/// This is implemented for every [`Entity`], so heterogeneous collections
+/// of tables and other entities can be handled generically,
+/// such as for logging.
+pub trait DynEntity {
+    /// Entity GUID
+    fn guid(&self) -> Guid;
+
+    /// Entity Name
+    fn name(&self) -> &'static str;
+}
+
+impl<T: Entity> DynEntity for T {
+    #[inline]
+    fn guid(&self) -> Guid {
+        T::GUID
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        T::NAME
+    }
+}
+
 /// Defines a UEFI Protocol
 ///
 /// See [`crate::Protocol`] for how to implement this.
@@ -74,6 +101,8 @@ pub unsafe trait Protocol<'table> {
     ///
     /// - `this` must be a valid pointer to a firmware instance of
     ///   [`Protocol::Raw`]
+    ///
+    /// In debug builds this asserts `this` is not null.
     #[doc(hidden)]
     unsafe fn from_raw(this: *mut Self::Raw) -> Self;
 
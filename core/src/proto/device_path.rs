@@ -12,10 +12,12 @@
 //!
 //! [s10]: <https://uefi.org/specs/UEFI/2.10/10_Protocols_Device_Path_Protocol.html>
 
-use core::ffi::c_void;
+use core::{ffi::c_void, mem::size_of};
 
 use nuefi_macros::GUID;
 
+use crate::error::{Result, Status};
+
 pub mod devpath_fn {
     //! Function definitions for [`super::DevicePathHdr`]
     //!
@@ -38,6 +40,9 @@ pub mod devpath_fn {
         this: *mut DevicePathHdr,
         other: *mut DevicePathHdr,
     ) -> *mut DevicePathHdr;
+
+    pub type CreateDeviceNode =
+        unsafe extern "efiapi" fn(ty: u8, sub_ty: u8, len: u16) -> *mut DevicePathHdr;
 }
 
 mod imp {
@@ -138,6 +143,41 @@ impl DevicePathHdr {
         }
     }
 
+    /// Validate that `bytes` contains a well formed device path,
+    /// returning a reference to its first node.
+    ///
+    /// Every nodes length is checked to be at least a header,
+    /// and to lie within `bytes`, and the path must end with an
+    /// [End Of Path][`DevicePathSubType::END_ENTIRE`] node within `bytes`.
+    ///
+    /// This should be used before traversing a device path from untrusted
+    /// bytes, such as a UEFI variable.
+    ///
+    /// Returns [`Status::INVALID_PARAMETER`] if the path is malformed.
+    pub fn validate(bytes: &[u8]) -> Result<&DevicePathHdr> {
+        let hdr_size = size_of::<DevicePathHdr>();
+        let mut rest = bytes;
+        loop {
+            if rest.len() < hdr_size {
+                return Err(Status::INVALID_PARAMETER.into());
+            }
+            // Safety: `rest` is valid for at least a header,
+            // which is packed, so alignment doesn't matter.
+            let hdr = unsafe { &*(rest.as_ptr() as *const DevicePathHdr) };
+            let len = u16::from_le_bytes(hdr.len) as usize;
+            if len < hdr_size || len > rest.len() {
+                return Err(Status::INVALID_PARAMETER.into());
+            }
+            if hdr.ty == DevicePathType::END && hdr.sub_ty == DevicePathSubType::END_ENTIRE {
+                break;
+            }
+            rest = &rest[len..];
+        }
+        // Safety: Checked above that `bytes` holds at least one node,
+        // which is packed, so alignment doesn't matter.
+        Ok(unsafe { &*(bytes.as_ptr() as *const DevicePathHdr) })
+    }
+
     /// Create a media filepath node for a null terminated path of bytes `len`
     pub fn media_file(len: u16) -> Self {
         let len = len.checked_add(4).unwrap();
@@ -160,7 +200,7 @@ pub struct DevicePathUtil {
     pub append_device_path_instance: *mut c_void,
     pub get_next_device_path_instance: *mut c_void,
     pub is_device_path_multi_instance: *mut c_void,
-    pub create_device_node: *mut c_void,
+    pub create_device_node: Option<devpath_fn::CreateDeviceNode>,
 }
 
 /// Device Path Display protocol
@@ -183,3 +223,27 @@ pub struct DevicePathToText {
         ) -> *mut u16,
     >,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        // Media file node for `a`, then End Of Path
+        let path = [4, 4, 8, 0, b'a', 0, 0, 0, 0x7F, 0xFF, 4, 0];
+        assert!(DevicePathHdr::validate(&path).is_ok());
+
+        // Missing End Of Path
+        assert!(DevicePathHdr::validate(&path[..8]).is_err());
+
+        // Node length past the end of the buffer
+        let mut bad = path;
+        bad[2] = 0x40;
+        assert!(DevicePathHdr::validate(&bad).is_err());
+
+        // Node length smaller than a header
+        bad[2] = 2;
+        assert!(DevicePathHdr::validate(&bad).is_err());
+    }
+}
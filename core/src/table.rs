@@ -54,6 +54,97 @@ impl Revision {
     }
 }
 
+/// Vendor specific firmware revision
+///
+/// UEFI does not define the format of this value,
+/// but some vendors encode `major.minor` as BCD in the upper and lower
+/// 16 bits.
+///
+/// The [`Display`][core::fmt::Display] implementation shows the raw value,
+/// and a best-effort `major.minor` interpretation.
+///
+/// Same representation as [`u32`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct FirmwareRevision(pub u32);
+
+impl FirmwareRevision {
+    /// Create a new firmware revision from the raw vendor value
+    #[inline]
+    pub const fn new(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    /// Create a new BCD encoded firmware revision for `major.minor`
+    ///
+    /// Returns [`None`] if either part is larger than `9999`
+    #[inline]
+    pub const fn from_bcd(major: u16, minor: u16) -> Option<Self> {
+        match (to_bcd(major), to_bcd(minor)) {
+            (Some(major), Some(minor)) => Some(Self(((major as u32) << 16) | minor as u32)),
+            _ => None,
+        }
+    }
+
+    /// The raw vendor value
+    #[inline]
+    pub const fn raw(self) -> u32 {
+        self.0
+    }
+
+    /// Interpret this revision as BCD encoded `(major, minor)`
+    ///
+    /// Returns [`None`] if either half is not valid BCD
+    #[inline]
+    pub const fn to_bcd(self) -> Option<(u16, u16)> {
+        match (from_bcd((self.0 >> 16) as u16), from_bcd(self.0 as u16)) {
+            (Some(major), Some(minor)) => Some((major, minor)),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for FirmwareRevision {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (major, minor) = match self.to_bcd() {
+            Some(v) => v,
+            None => ((self.0 >> 16) as u16, self.0 as u16),
+        };
+        write!(f, "{:#010X} ({major}.{minor})", self.0)
+    }
+}
+
+/// Encode `v` as 4 BCD digits
+const fn to_bcd(mut v: u16) -> Option<u16> {
+    if v > 9999 {
+        return None;
+    }
+    let mut out = 0;
+    let mut shift = 0;
+    while v != 0 {
+        out |= (v % 10) << shift;
+        v /= 10;
+        shift += 4;
+    }
+    Some(out)
+}
+
+/// Decode 4 BCD digits from `v`
+const fn from_bcd(mut v: u16) -> Option<u16> {
+    let mut out = 0;
+    let mut scale = 1;
+    while v != 0 {
+        let digit = v & 0xF;
+        if digit > 9 {
+            return None;
+        }
+        out += digit * scale;
+        v >>= 4;
+        scale *= 10;
+    }
+    Some(out)
+}
+
 /// The common header of the 3 UEFI tables, System, Boot, and Runtime.
 ///
 /// This structure precedes the UEFI defined tables. UEFI tables are dynamically
@@ -106,6 +197,14 @@ pub struct Header {
 }
 
 impl Header {
+    /// Minimum UEFI revision accepted by [`Header::validate`]
+    pub const MIN_REVISION: Revision = Revision::new(2, 0);
+
+    /// First UEFI revision *not* accepted by [`Header::validate`]
+    ///
+    /// A hypothetical UEFI `3.x` may be incompatible
+    pub const MAX_REVISION: Revision = Revision::new(3, 0);
+
     /// Validate the header for a table with signature `sig`
     ///
     /// This does some basic sanity checks on the UEFI system table,
@@ -117,8 +216,9 @@ impl Header {
     /// - Verify that [`Header::signature`] matches `sig`
     /// - Verify that [`Header::size`] is at least as expected by `sig` because
     ///   we're paranoid
-    /// - Verify [`Header::revision`] is `2.x`. EFI `1.x` is not supported, and
-    ///   a hypothetical UEFI `3.x` is not
+    /// - Verify [`Header::revision`] is at least [`Header::MIN_REVISION`],
+    ///   and less than [`Header::MAX_REVISION`].
+    ///   EFI `1.x` is not supported
     /// - Verify [`Header::crc32`] over [`Header::size`] bytes
     ///
     /// # Safety
@@ -129,8 +229,18 @@ impl Header {
     ///   - Be valid for [`Header::size`] bytes
     ///   - Contain a valid table as determined by `sig`
     pub unsafe fn validate(table: *const u8, sig: u64) -> Result<()> {
+        // Safety: Callers responsibility
+        unsafe { Self::check(table, sig) }.map_err(|e| e.status().into())
+    }
+
+    /// Like [`Header::validate`], but returns why validation failed
+    ///
+    /// # Safety
+    ///
+    /// See [`Header::validate`]
+    pub unsafe fn check(table: *const u8, sig: u64) -> core::result::Result<(), InvalidReason> {
         if table.is_null() {
-            return Status::INVALID_PARAMETER.into();
+            return Err(InvalidReason::Null);
         }
 
         // Safety:
@@ -141,7 +251,7 @@ impl Header {
         let len = header.size as usize;
 
         if header.signature != sig {
-            return Status::INVALID_PARAMETER.into();
+            return Err(InvalidReason::Signature);
         }
 
         let expected_size = if sig == SystemTable::SIGNATURE {
@@ -151,16 +261,16 @@ impl Header {
         } else if sig == BootServices::SIGNATURE {
             size_of::<BootServices>()
         } else {
-            return Status::INVALID_PARAMETER.into();
+            return Err(InvalidReason::Signature);
         };
 
         // Make sure size is enough
         if len < expected_size {
-            return Status::INVALID_PARAMETER.into();
+            return Err(InvalidReason::Size);
         }
 
-        if header.revision.major() != 2 {
-            return Status::INCOMPATIBLE_VERSION.into();
+        if header.revision < Self::MIN_REVISION || header.revision >= Self::MAX_REVISION {
+            return Err(InvalidReason::Revision);
         }
 
         let expected = header.crc32;
@@ -180,7 +290,7 @@ impl Header {
         unsafe {
             let rem = len
                 .checked_sub(size_of::<Header>())
-                .ok_or(Status::INVALID_PARAMETER)?;
+                .ok_or(InvalidReason::Size)?;
             // This is always in bounds or 1 past the end because
             // we check the size after the signature
             // `rem` will be valid or the function returned
@@ -192,12 +302,100 @@ impl Header {
         };
 
         if expected != digest.finalize() {
-            return Status::CRC_ERROR.into();
+            return Err(InvalidReason::Crc);
         }
         Ok(())
     }
 }
 
+/// Why a table failed [`Header::validate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidReason {
+    /// The table pointer was null
+    Null,
+
+    /// [`Header::signature`] did not match
+    Signature,
+
+    /// [`Header::size`] was too small
+    Size,
+
+    /// [`Header::revision`] is unsupported
+    Revision,
+
+    /// [`Header::crc32`] did not match
+    Crc,
+}
+
+impl InvalidReason {
+    /// The [`Status`] [`Header::validate`] returns for this reason
+    pub const fn status(self) -> Status {
+        match self {
+            Self::Revision => Status::INCOMPATIBLE_VERSION,
+            Self::Crc => Status::CRC_ERROR,
+            _ => Status::INVALID_PARAMETER,
+        }
+    }
+}
+
+impl core::fmt::Display for InvalidReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Null => "table was null",
+            Self::Signature => "signature mismatch",
+            Self::Size => "table size too small",
+            Self::Revision => "unsupported revision",
+            Self::Crc => "CRC mismatch",
+        })
+    }
+}
+
+/// Which UEFI table failed validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableKind {
+    /// The [`SystemTable`]
+    System,
+
+    /// The [`BootServices`] table
+    Boot,
+
+    /// The [`RuntimeServices`] table
+    Runtime,
+}
+
+impl core::fmt::Display for TableKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::System => "System",
+            Self::Boot => "Boot Services",
+            Self::Runtime => "Runtime Services",
+        })
+    }
+}
+
+/// A table that failed [`SystemTable::check`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidTable {
+    /// The table that failed
+    pub table: TableKind,
+
+    /// Why it failed
+    pub reason: InvalidReason,
+}
+
+impl InvalidTable {
+    /// The [`Status`] [`SystemTable::validate`] returns for this error
+    pub const fn status(self) -> Status {
+        self.reason.status()
+    }
+}
+
+impl core::fmt::Display for InvalidTable {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "UEFI {} table is invalid: {}", self.table, self.reason)
+    }
+}
+
 /// The EFI system table.
 ///
 /// After a call to [`ExitBootServices`], only the following fields are valid:
@@ -308,17 +506,32 @@ impl SystemTable {
     ///
     /// See [`Header::validate`] for details
     pub unsafe fn validate(this: *mut Self) -> Result<()> {
+        // Safety: Callers responsibility
+        unsafe { Self::check(this) }.map_err(|e| e.status().into())
+    }
+
+    /// Like [`SystemTable::validate`], but returns which table failed
+    /// validation, and why.
+    ///
+    /// # Safety
+    ///
+    /// See [`SystemTable::validate`]
+    pub unsafe fn check(this: *mut Self) -> core::result::Result<(), InvalidTable> {
+        let fail = |table| move |reason| InvalidTable { table, reason };
+
         // Safety: Validating ourself, callers responsibility
-        Header::validate(this as *const u8, Self::SIGNATURE)?;
+        Header::check(this as *const u8, Self::SIGNATURE).map_err(fail(TableKind::System))?;
 
         let header = &(*this);
 
         // Safety: Callers responsibility
-        Header::validate(header.boot_services as *const u8, BootServices::SIGNATURE)?;
-        Header::validate(
+        Header::check(header.boot_services as *const u8, BootServices::SIGNATURE)
+            .map_err(fail(TableKind::Boot))?;
+        Header::check(
             header.runtime_services as *const u8,
             RuntimeServices::SIGNATURE,
-        )?;
+        )
+        .map_err(fail(TableKind::Runtime))?;
 
         Ok(())
     }
@@ -347,6 +560,37 @@ impl LocateSearch {
     pub const BY_PROTOCOL: Self = Self(2);
 }
 
+/// Interface type for [`BootServices::install_protocol_interface`]
+///
+/// This is FFI compatible with and ABI Identical to a [`u32`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct InterfaceType(u32);
+
+impl InterfaceType {
+    /// The interface is in the native format of the platform.
+    ///
+    /// This is the only interface type defined by UEFI.
+    pub const NATIVE: Self = Self(0);
+}
+
+/// An entry returned by [`BootServices::open_protocol_information`]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct OpenProtocolInformationEntry {
+    /// The agent that opened the protocol
+    pub agent_handle: Handle,
+
+    /// The controller the protocol was opened for, may be null
+    pub controller_handle: Handle,
+
+    /// Attributes the protocol was opened with
+    pub attributes: u32,
+
+    /// How many times the protocol has been opened
+    pub open_count: u32,
+}
+
 /// The UEFI Boot Services Table
 ///
 /// This is FFI-safe
@@ -356,8 +600,8 @@ pub struct BootServices {
     pub header: Header,
 
     // Task priority
-    pub raise_tpl: *mut c_void,
-    pub restore_tpl: *mut c_void,
+    pub raise_tpl: Option<boot_fn::RaiseTpl>,
+    pub restore_tpl: Option<boot_fn::RestoreTpl>,
 
     // Memory
     pub allocate_pages: Option<boot_fn::AllocatePages>,
@@ -371,24 +615,24 @@ pub struct BootServices {
     pub free_pool: Option<boot_fn::FreePool>,
 
     // Timers/Events
-    pub create_event: *mut c_void,
-    pub set_timer: *mut c_void,
-    pub wait_for_event: *mut c_void,
-    pub signal_event: *mut c_void,
-    pub close_event: *mut c_void,
-    pub check_event: *mut c_void,
+    pub create_event: Option<boot_fn::CreateEvent>,
+    pub set_timer: Option<boot_fn::SetTimer>,
+    pub wait_for_event: Option<boot_fn::WaitForEvent>,
+    pub signal_event: Option<boot_fn::SignalEvent>,
+    pub close_event: Option<boot_fn::CloseEvent>,
+    pub check_event: Option<boot_fn::CheckEvent>,
 
     // Protocols
     pub install_protocol_interface: Option<boot_fn::InstallProtocolInterface>,
-    pub reinstall_protocol_interface: *mut c_void,
-    pub uninstall_protocol_interface: *mut c_void,
+    pub reinstall_protocol_interface: Option<boot_fn::ReinstallProtocolInterface>,
+    pub uninstall_protocol_interface: Option<boot_fn::UninstallProtocolInterface>,
     pub handle_protocol: Option<boot_fn::HandleProtocolFn>,
     pub _reserved: *mut c_void,
-    pub register_protocol_notify: *mut c_void,
+    pub register_protocol_notify: Option<boot_fn::RegisterProtocolNotify>,
 
     pub locate_handle: Option<boot_fn::LocateHandle>,
 
-    pub locate_device_path: *mut c_void,
+    pub locate_device_path: Option<boot_fn::LocateDevicePath>,
     pub install_configuration_table: Option<boot_fn::InstallConfigurationTable>,
 
     // Images
@@ -410,31 +654,34 @@ pub struct BootServices {
     pub set_watchdog_timer: Option<boot_fn::SetWatchdogTimer>,
 
     // Drivers
-    pub connect_controller: *mut c_void,
-    pub disconnect_controller: *mut c_void,
+    pub connect_controller: Option<boot_fn::ConnectController>,
+    pub disconnect_controller: Option<boot_fn::DisconnectController>,
 
     // Protocols again
     pub open_protocol: Option<boot_fn::OpenProtocol>,
 
     pub close_protocol: Option<boot_fn::CloseProtocol>,
-    pub open_protocol_information: *mut c_void,
+
+    pub open_protocol_information: Option<boot_fn::OpenProtocolInformation>,
 
     // Library?
-    pub protocols_per_handle: *mut c_void,
-    pub locate_handle_buffer: *mut c_void,
+    pub protocols_per_handle: Option<boot_fn::ProtocolsPerHandle>,
+    pub locate_handle_buffer: Option<boot_fn::LocateHandleBuffer>,
 
     pub locate_protocol: Option<boot_fn::LocateProtocolFn>,
 
-    pub install_multiple_protocol_interfaces: *mut c_void,
-    pub uninstall_multiple_protocol_interfaces: *mut c_void,
+    // These are C variadic functions, modeled with a fixed number of arguments
+    pub install_multiple_protocol_interfaces: Option<boot_fn::InstallMultipleProtocolInterfaces>,
+    pub uninstall_multiple_protocol_interfaces:
+        Option<boot_fn::UninstallMultipleProtocolInterfaces>,
 
     // Useless CRC
-    pub calculate_crc32: *mut c_void,
+    pub calculate_crc32: Option<boot_fn::CalculateCrc32>,
 
     // Misc again
-    pub copy_mem: *mut c_void,
-    pub set_mem: *mut c_void,
-    pub create_event_ex: *mut c_void,
+    pub copy_mem: Option<boot_fn::CopyMem>,
+    pub set_mem: Option<boot_fn::SetMem>,
+    pub create_event_ex: Option<boot_fn::CreateEventEx>,
 }
 
 impl BootServices {
@@ -457,6 +704,8 @@ impl RuntimeServices {
 
 #[cfg(test)]
 mod tests {
+    use alloc::{boxed::Box, string::ToString};
+
     use super::*;
 
     #[test]
@@ -465,4 +714,86 @@ mod tests {
         assert_eq!(rev.major(), 2);
         assert_eq!(rev.minor(), 70);
     }
+
+    #[test]
+    fn firmware_revision() {
+        let rev = FirmwareRevision::from_bcd(12, 34).unwrap();
+        assert_eq!(rev.raw(), 0x0012_0034);
+        assert_eq!(rev.to_bcd(), Some((12, 34)));
+        assert_eq!(rev.to_string(), "0x00120034 (12.34)");
+        assert_eq!(FirmwareRevision::from_bcd(10000, 0), None);
+
+        // Not BCD, so falls back to the raw halves
+        let rev = FirmwareRevision::new(0x0001_000A);
+        assert_eq!(rev.to_bcd(), None);
+        assert_eq!(rev.to_string(), "0x0001000A (1.10)");
+    }
+
+    #[test]
+    fn check_reports_table() {
+        let b = [0u8; size_of::<SystemTable>()];
+        // Safety: All fields of `SystemTable` are safely nullable/zero
+        let mut table: SystemTable = unsafe { core::mem::transmute(b) };
+
+        // Safety: `table` is valid for its size
+        let err = unsafe { SystemTable::check(&mut table) }.unwrap_err();
+        assert_eq!(err.table, TableKind::System);
+        assert_eq!(err.reason, InvalidReason::Signature);
+        assert_eq!(err.status(), Status::INVALID_PARAMETER);
+    }
+
+    /// A zeroed `T` with a valid [`Header`] for `sig`
+    ///
+    /// # Safety
+    ///
+    /// - `T` must start with a [`Header`], and be safely zeroable
+    unsafe fn table<T>(sig: u64) -> Box<T> {
+        // Safety: Callers responsibility
+        let mut t: Box<T> = Box::new(unsafe { core::mem::zeroed() });
+        // Safety: Callers responsibility
+        let header = unsafe { &mut *(&mut *t as *mut T).cast::<Header>() };
+        header.signature = sig;
+        header.revision = SystemTable::REVISION;
+        header.size = size_of::<T>() as u32;
+        t
+    }
+
+    /// Fill in the CRC for `t`
+    ///
+    /// # Safety
+    ///
+    /// - `T` must start with a [`Header`], and have no uninit padding
+    unsafe fn seal<T>(t: &mut T) {
+        let ptr = (t as *mut T).cast::<u8>();
+        // Safety: Callers responsibility
+        unsafe { (*ptr.cast::<Header>()).crc32 = 0 };
+        // Safety: Callers responsibility
+        let bytes = unsafe { core::slice::from_raw_parts(ptr, size_of::<T>()) };
+        let crc = CRC.checksum(bytes);
+        // Safety: Callers responsibility
+        unsafe { (*ptr.cast::<Header>()).crc32 = crc };
+    }
+
+    #[test]
+    fn check_reaches_runtime() {
+        // Safety: All these tables start with a header and are zeroable
+        unsafe {
+            let mut boot = table::<BootServices>(BootServices::SIGNATURE);
+            let mut run = table::<RuntimeServices>(RuntimeServices::SIGNATURE);
+            let mut system = table::<SystemTable>(SystemTable::SIGNATURE);
+            seal(&mut *boot);
+            // Corrupt runtime services
+            run.header.crc32 = 0xDEAD;
+            system.boot_services = &mut *boot;
+            system.runtime_services = &mut *run;
+            seal(&mut *system);
+
+            let err = SystemTable::check(&mut *system).unwrap_err();
+            assert_eq!(err.table, TableKind::Runtime);
+            assert_eq!(err.reason, InvalidReason::Crc);
+
+            seal(&mut *run);
+            assert_eq!(SystemTable::check(&mut *system), Ok(()));
+        }
+    }
 }
@@ -6,7 +6,7 @@
 //! - <https://uefi.org/specs/UEFI/2.10/07_Services_Boot_Services.html>
 use core::ffi::c_void;
 
-use super::{mem::*, LocateSearch};
+use super::{mem::*, InterfaceType, LocateSearch, OpenProtocolInformationEntry};
 use crate::base::*;
 
 // FIXME: Hack
@@ -43,10 +43,14 @@ pub type AllocatePool = unsafe extern "efiapi" fn(
 
 pub type FreePool = unsafe extern "efiapi" fn(mem: *mut c_void) -> Status;
 
+pub type SignalEvent = unsafe extern "efiapi" fn(event: Event) -> Status;
+
+pub type CheckEvent = unsafe extern "efiapi" fn(event: Event) -> Status;
+
 pub type InstallProtocolInterface = unsafe extern "efiapi" fn(
     handle: *mut Handle,
     guid: *mut Guid,
-    interface_ty: u32,
+    interface_ty: InterfaceType,
     interface: *mut c_void,
 ) -> Status;
 
@@ -131,3 +135,149 @@ pub type CloseProtocol = unsafe extern "efiapi" fn(
     agent_handle: Handle,
     controller_handle: Handle,
 ) -> Status;
+
+pub type OpenProtocolInformation = unsafe extern "efiapi" fn(
+    handle: Handle,
+    guid: *mut Guid,
+    entries: *mut *mut OpenProtocolInformationEntry,
+    count: *mut usize,
+) -> Status;
+
+/// Compute the CRC32 of `size` bytes of `data`, storing it in `crc`
+pub type CalculateCrc32 =
+    unsafe extern "efiapi" fn(data: *const c_void, size: usize, crc: *mut u32) -> Status;
+
+pub type RaiseTpl = unsafe extern "efiapi" fn(new_tpl: TaskPriorityLevel) -> TaskPriorityLevel;
+
+pub type RestoreTpl = unsafe extern "efiapi" fn(old_tpl: TaskPriorityLevel);
+
+/// Notification function for an [`Event`]
+pub type EventNotify = unsafe extern "efiapi" fn(event: Event, context: *mut c_void);
+
+pub type CreateEvent = unsafe extern "efiapi" fn(
+    ty: u32,
+    notify_tpl: TaskPriorityLevel,
+    notify: Option<EventNotify>,
+    context: *mut c_void,
+    event: *mut Event,
+) -> Status;
+
+pub type CreateEventEx = unsafe extern "efiapi" fn(
+    ty: u32,
+    notify_tpl: TaskPriorityLevel,
+    notify: Option<EventNotify>,
+    context: *const c_void,
+    event_group: *const Guid,
+    event: *mut Event,
+) -> Status;
+
+pub type SetTimer = unsafe extern "efiapi" fn(
+    //
+    event: Event,
+    ty: u32,
+    trigger_time: u64,
+) -> Status;
+
+pub type WaitForEvent = unsafe extern "efiapi" fn(
+    //
+    number_of_events: usize,
+    events: *mut Event,
+    index: *mut usize,
+) -> Status;
+
+pub type CloseEvent = unsafe extern "efiapi" fn(event: Event) -> Status;
+
+pub type ReinstallProtocolInterface = unsafe extern "efiapi" fn(
+    handle: Handle,
+    guid: *mut Guid,
+    old_interface: *mut c_void,
+    new_interface: *mut c_void,
+) -> Status;
+
+pub type UninstallProtocolInterface =
+    unsafe extern "efiapi" fn(handle: Handle, guid: *mut Guid, interface: *mut c_void) -> Status;
+
+pub type RegisterProtocolNotify = unsafe extern "efiapi" fn(
+    guid: *mut Guid,
+    event: Event,
+    registration: *mut *mut c_void,
+) -> Status;
+
+pub type LocateDevicePath = unsafe extern "efiapi" fn(
+    guid: *mut Guid,
+    path: *mut *mut DevicePath,
+    device: *mut Handle,
+) -> Status;
+
+pub type ConnectController = unsafe extern "efiapi" fn(
+    controller: Handle,
+    driver_image: *mut Handle,
+    remaining_path: *mut DevicePath,
+    recursive: bool,
+) -> Status;
+
+pub type DisconnectController =
+    unsafe extern "efiapi" fn(controller: Handle, driver_image: Handle, child: Handle) -> Status;
+
+pub type ProtocolsPerHandle = unsafe extern "efiapi" fn(
+    handle: Handle,
+    buffer: *mut *mut *mut Guid,
+    count: *mut usize,
+) -> Status;
+
+pub type LocateHandleBuffer = unsafe extern "efiapi" fn(
+    search_type: LocateSearch,
+    protocol: *const Guid,
+    search_key: *const c_void,
+    count: *mut usize,
+    buffer: *mut *mut Handle,
+) -> Status;
+
+pub type CopyMem = unsafe extern "efiapi" fn(dest: *mut c_void, src: *const c_void, len: usize);
+
+pub type SetMem = unsafe extern "efiapi" fn(buffer: *mut c_void, size: usize, value: u8);
+
+/// Maximum number of `(Guid, Interface)` pairs that can be passed to
+/// [`InstallMultipleProtocolInterfaces`] and
+/// [`UninstallMultipleProtocolInterfaces`]
+pub const MAX_MULTIPLE_INTERFACES: usize = 4;
+
+/// `InstallMultipleProtocolInterfaces`
+///
+/// This is a C variadic function, taking a null terminated list of
+/// `(Guid, Interface)` pointer pairs, which Rust can't define for `efiapi`.
+///
+/// It is modeled here as taking [`MAX_MULTIPLE_INTERFACES`] pairs and
+/// a terminator.
+/// Pointer arguments are passed the same whether or not the function is
+/// variadic on every UEFI calling convention, and firmware stops reading at
+/// the first null GUID, so every unused argument must be null.
+pub type InstallMultipleProtocolInterfaces = unsafe extern "efiapi" fn(
+    handle: *mut Handle,
+    guid0: *const Guid,
+    interface0: *mut c_void,
+    guid1: *const Guid,
+    interface1: *mut c_void,
+    guid2: *const Guid,
+    interface2: *mut c_void,
+    guid3: *const Guid,
+    interface3: *mut c_void,
+    end: *const Guid,
+) -> Status;
+
+/// `UninstallMultipleProtocolInterfaces`
+///
+/// See [`InstallMultipleProtocolInterfaces`] for how this variadic function
+/// is modeled.
+pub type UninstallMultipleProtocolInterfaces = unsafe extern "efiapi" fn(
+    handle: Handle,
+    guid0: *const Guid,
+    interface0: *mut c_void,
+    guid1: *const Guid,
+    interface1: *mut c_void,
+    guid2: *const Guid,
+    interface2: *mut c_void,
+    guid3: *const Guid,
+    interface3: *mut c_void,
+    end: *const Guid,
+) -> Status;
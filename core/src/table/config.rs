@@ -9,9 +9,9 @@
 //! Several standard and vendor-specific tables are defined and known about
 //! here. Unknown tables can be used through [`GenericConfig`]
 #![allow(dead_code)]
-use core::{ffi::c_void, marker::PhantomData};
+use core::{ffi::c_void, marker::PhantomData, mem::size_of};
 
-use crate::{base::*, extra::Entity, GUID};
+use crate::{base::*, extra::Entity, table::mem::MemoryDescriptor, GUID};
 
 mod imp {
     use super::*;
@@ -107,6 +107,41 @@ pub trait ConfigTable<'tbl>: Entity + imp::Sealed {
     unsafe fn from_raw(raw: *const c_void) -> Self::Out<'tbl>;
 }
 
+/// Known configuration tables, sorted by [`Guid`]
+///
+/// NOTE: Manually keep up to date, and sorted.
+/// The `known_tables_sorted` test verifies this.
+static KNOWN_TABLES: &[(Guid, &str)] = &[
+    (UserInformation::GUID, UserInformation::NAME),
+    (MemoryAttributes::GUID, MemoryAttributes::NAME),
+    (JsonCapsuleData::GUID, JsonCapsuleData::NAME),
+    (MemoryStatus::GUID, MemoryStatus::NAME),
+    (MPS::GUID, MPS::NAME),
+    (AcpiTable10::GUID, AcpiTable10::NAME),
+    (SMBIOS::GUID, SMBIOS::NAME),
+    (SAL::GUID, SAL::NAME),
+    (SMBIOS3::GUID, SMBIOS3::NAME),
+    (ConformanceProfile::GUID, ConformanceProfile::NAME),
+    (HOBlist::GUID, HOBlist::NAME),
+    (BrotliCompress::GUID, BrotliCompress::NAME),
+    (SystemResource::GUID, SystemResource::NAME),
+    (AcpiTable20::GUID, AcpiTable20::NAME),
+    (HIIDatabaseExport::GUID, HIIDatabaseExport::NAME),
+    (DebugImageInfo::GUID, DebugImageInfo::NAME),
+    (JsonConfigData::GUID, JsonConfigData::NAME),
+    (RuntimeProperties::GUID, RuntimeProperties::NAME),
+    (LZMACompress::GUID, LZMACompress::NAME),
+    (MemoryTypeInfo::GUID, MemoryTypeInfo::NAME),
+    (EfiProperties::GUID, EfiProperties::NAME),
+    (TianoCompress::GUID, TianoCompress::NAME),
+    (DXEServices::GUID, DXEServices::NAME),
+    (LZMAf86Compress::GUID, LZMAf86Compress::NAME),
+    (JsonCapsuleResult::GUID, JsonCapsuleResult::NAME),
+    (ImageExecInfo::GUID, ImageExecInfo::NAME),
+    (DeviceTree::GUID, DeviceTree::NAME),
+    (MemoryRangeCapsule::GUID, MemoryRangeCapsule::NAME),
+];
+
 /// A generic UEFI configuration table, identified by a [`Guid`]
 #[derive(Debug)]
 #[repr(transparent)]
@@ -144,68 +179,11 @@ impl<'tbl> GenericConfig<'tbl> {
 
     /// Name of this table, if known
     pub fn name(&self) -> Option<&'static str> {
-        // NOTE: Manually keep up to date.
-        // TODO: Find better way?
         let guid = self.guid();
-        if guid == AcpiTable20::GUID {
-            Some(AcpiTable20::NAME)
-        } else if guid == AcpiTable10::GUID {
-            Some(AcpiTable10::NAME)
-        } else if guid == RuntimeProperties::GUID {
-            Some(RuntimeProperties::NAME)
-        } else if guid == SMBIOS::GUID {
-            Some(SMBIOS::NAME)
-        } else if guid == SMBIOS3::GUID {
-            Some(SMBIOS3::NAME)
-        } else if guid == SAL::GUID {
-            Some(SAL::NAME)
-        } else if guid == MPS::GUID {
-            Some(MPS::NAME)
-        } else if guid == JsonConfigData::GUID {
-            Some(JsonConfigData::NAME)
-        } else if guid == JsonCapsuleData::GUID {
-            Some(JsonCapsuleData::NAME)
-        } else if guid == JsonCapsuleResult::GUID {
-            Some(JsonCapsuleResult::NAME)
-        } else if guid == DeviceTree::GUID {
-            Some(DeviceTree::NAME)
-        } else if guid == MemoryAttributes::GUID {
-            Some(MemoryAttributes::NAME)
-        } else if guid == ConformanceProfile::GUID {
-            Some(ConformanceProfile::NAME)
-        } else if guid == DebugImageInfo::GUID {
-            Some(DebugImageInfo::NAME)
-        } else if guid == ImageExecInfo::GUID {
-            Some(ImageExecInfo::NAME)
-        } else if guid == SystemResource::GUID {
-            Some(SystemResource::NAME)
-        } else if guid == MemoryRangeCapsule::GUID {
-            Some(MemoryRangeCapsule::NAME)
-        } else if guid == UserInformation::GUID {
-            Some(UserInformation::NAME)
-        } else if guid == HIIDatabaseExport::GUID {
-            Some(HIIDatabaseExport::NAME)
-        } else if guid == EfiProperties::GUID {
-            Some(EfiProperties::NAME)
-        } else if guid == TianoCompress::GUID {
-            Some(TianoCompress::NAME)
-        } else if guid == LZMACompress::GUID {
-            Some(LZMACompress::NAME)
-        } else if guid == BrotliCompress::GUID {
-            Some(BrotliCompress::NAME)
-        } else if guid == LZMAf86Compress::GUID {
-            Some(LZMAf86Compress::NAME)
-        } else if guid == DXEServices::GUID {
-            Some(DXEServices::NAME)
-        } else if guid == HOBlist::GUID {
-            Some(HOBlist::NAME)
-        } else if guid == MemoryTypeInfo::GUID {
-            Some(MemoryTypeInfo::NAME)
-        } else if guid == MemoryStatus::GUID {
-            Some(MemoryStatus::NAME)
-        } else {
-            None
-        }
+        KNOWN_TABLES
+            .binary_search_by(|(g, _)| g.cmp(&guid))
+            .ok()
+            .map(|i| KNOWN_TABLES[i].1)
     }
 
     /// If this generic table is [`ConfigTable`] `T`,
@@ -238,6 +216,20 @@ impl AcpiTable20 {
     pub const fn table(&self) -> *mut c_void {
         self.table
     }
+
+    /// The ACPI 2.0 RSDP this table points to,
+    /// or [`None`] if it is invalid.
+    ///
+    /// See [`RsdpV2::is_valid`]
+    pub fn rsdp(&self) -> Option<&RsdpV2> {
+        if self.table.is_null() {
+            return None;
+        }
+        // Safety: Construction ensures `table` points to the RSDP.
+        // `RsdpV2` is packed, so the pointer is always aligned.
+        let rsdp = unsafe { &*self.table.cast::<RsdpV2>() };
+        rsdp.is_valid().then_some(rsdp)
+    }
 }
 
 /// Table for ACPI 1.0
@@ -247,6 +239,138 @@ pub struct AcpiTable10 {
     table: *mut c_void,
 }
 
+impl AcpiTable10 {
+    #[inline]
+    pub const fn table(&self) -> *mut c_void {
+        self.table
+    }
+
+    /// The ACPI 1.0 RSDP this table points to,
+    /// or [`None`] if it is invalid.
+    ///
+    /// See [`RsdpV1::is_valid`]
+    pub fn rsdp(&self) -> Option<&RsdpV1> {
+        if self.table.is_null() {
+            return None;
+        }
+        // Safety: Construction ensures `table` points to the RSDP.
+        // `RsdpV1` is packed, so the pointer is always aligned.
+        let rsdp = unsafe { &*self.table.cast::<RsdpV1>() };
+        rsdp.is_valid().then_some(rsdp)
+    }
+}
+
+/// Sum of `bytes`, which is zero for a valid ACPI checksum
+fn acpi_checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b))
+}
+
+/// ACPI 1.0 Root System Description Pointer
+///
+/// See [`AcpiTable10::rsdp`]
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed)]
+pub struct RsdpV1 {
+    signature: [u8; 8],
+    checksum: u8,
+    oem_id: [u8; 6],
+    revision: u8,
+    rsdt_address: u32,
+}
+
+impl RsdpV1 {
+    /// The expected [`RsdpV1::signature`]
+    pub const SIGNATURE: [u8; 8] = *b"RSD PTR ";
+
+    /// Whether the signature and checksum are valid
+    pub fn is_valid(&self) -> bool {
+        // Safety: `Self` is packed plain data with no padding
+        let bytes = unsafe {
+            core::slice::from_raw_parts((self as *const Self).cast::<u8>(), size_of::<Self>())
+        };
+        self.signature == Self::SIGNATURE && acpi_checksum(bytes) == 0
+    }
+
+    /// The signature, `"RSD PTR "`
+    pub fn signature(&self) -> [u8; 8] {
+        self.signature
+    }
+
+    /// OEM supplied identifier
+    pub fn oem_id(&self) -> [u8; 6] {
+        self.oem_id
+    }
+
+    /// ACPI revision of this structure
+    ///
+    /// This is 0 for ACPI 1.0, and 2 for ACPI 2.0 and newer
+    pub fn revision(&self) -> u8 {
+        self.revision
+    }
+
+    /// 32-bit physical address of the RSDT
+    pub fn rsdt_address(&self) -> u32 {
+        self.rsdt_address
+    }
+}
+
+/// ACPI 2.0 and newer Root System Description Pointer
+///
+/// See [`AcpiTable20::rsdp`]
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed)]
+pub struct RsdpV2 {
+    v1: RsdpV1,
+    length: u32,
+    xsdt_address: u64,
+    extended_checksum: u8,
+    reserved: [u8; 3],
+}
+
+impl RsdpV2 {
+    /// Whether the signature, revision, and both checksums are valid
+    pub fn is_valid(&self) -> bool {
+        // Safety: `Self` is packed plain data with no padding
+        let bytes = unsafe {
+            core::slice::from_raw_parts((self as *const Self).cast::<u8>(), size_of::<Self>())
+        };
+        self.v1.is_valid()
+            && self.v1.revision >= 2
+            && self.length as usize >= size_of::<Self>()
+            && acpi_checksum(bytes) == 0
+    }
+
+    /// The ACPI 1.0 compatible part of this structure
+    pub fn v1(&self) -> &RsdpV1 {
+        &self.v1
+    }
+
+    /// The signature, `"RSD PTR "`
+    pub fn signature(&self) -> [u8; 8] {
+        self.v1.signature()
+    }
+
+    /// OEM supplied identifier
+    pub fn oem_id(&self) -> [u8; 6] {
+        self.v1.oem_id()
+    }
+
+    /// ACPI revision of this structure
+    pub fn revision(&self) -> u8 {
+        self.v1.revision()
+    }
+
+    /// Length of the entire table in bytes
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// 64-bit physical address of the XSDT
+    pub fn xsdt_address(&self) -> u64 {
+        self.xsdt_address
+    }
+}
+
 /// Table for SMBIOS 3
 #[GUID("F2FD1544-9794-4A2C-992E-E5BBCF20E394", crate("crate"))]
 #[derive(Debug)]
@@ -311,12 +435,91 @@ pub struct DeviceTree {
     table: *mut c_void,
 }
 
+/// Memory Attributes table
+///
+/// Describes the protections that should be applied to Runtime Services
+/// memory once mapped by the OS, such as code being read-only and data
+/// non-executable.
+///
+/// Each [`MemoryDescriptor`] covers part of a
+/// [Runtime][`crate::table::mem::MemoryType::is_runtime`] region from the
+/// memory map.
 #[GUID("DCFA911D-26EB-469F-A220-38B7DC461220", crate("crate"))]
 #[derive(Debug)]
 pub struct MemoryAttributes {
     table: *mut c_void,
 }
 
+/// Header of the [`MemoryAttributes`] table
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+struct RawMemoryAttributes {
+    version: u32,
+    entries: u32,
+    descriptor_size: u32,
+    flags: u32,
+}
+
+impl MemoryAttributes {
+    #[inline]
+    pub const fn table(&self) -> *mut c_void {
+        self.table
+    }
+
+    fn header(&self) -> &RawMemoryAttributes {
+        // Safety: Construction ensures `table` points to the table
+        unsafe { &*self.table.cast::<RawMemoryAttributes>() }
+    }
+
+    /// Version of this table
+    pub fn version(&self) -> u32 {
+        self.header().version
+    }
+
+    /// Size of each [`MemoryDescriptor`] in the table, in bytes
+    ///
+    /// This may be larger than [`MemoryDescriptor`]
+    pub fn descriptor_size(&self) -> u32 {
+        self.header().descriptor_size
+    }
+
+    /// Number of [`MemoryDescriptor`]s in the table
+    pub fn len(&self) -> usize {
+        self.header().entries as usize
+    }
+
+    /// Whether there are no [`MemoryDescriptor`]s in the table
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterator over every [`MemoryDescriptor`] in the table
+    ///
+    /// The [`MemoryDescriptor::attribute`] of each describes the
+    /// protections to apply to it.
+    ///
+    /// This is empty if [`MemoryAttributes::descriptor_size`] is
+    /// too small to be valid.
+    pub fn descriptors(&self) -> impl Iterator<Item = MemoryDescriptor> + '_ {
+        let size = self.descriptor_size() as usize;
+        let len = if size < size_of::<MemoryDescriptor>() {
+            0
+        } else {
+            self.len()
+        };
+        let header = size_of::<RawMemoryAttributes>();
+        // Safety: Construction ensures `table` points to the table,
+        // followed by `len` descriptors of `size` bytes each
+        let base = unsafe { self.table.cast::<u8>().add(header) };
+        // Safety: See above. `size` may not keep descriptors aligned.
+        (0..len).map(move |i| unsafe {
+            base.add(i * size)
+                .cast::<MemoryDescriptor>()
+                .read_unaligned()
+        })
+    }
+}
+
 /// UEFI Conformance profile
 #[derive(Debug)]
 #[repr(C)]
@@ -384,7 +587,7 @@ pub struct HIIDatabaseExport {
 }
 
 /// Deprecated Legacy EFI Properties
-#[GUID("EF9FC172-A1B2-4693-B327-6D32FC416042", crate("crate"))]
+#[GUID("880AACA3-4ADC-4A04-9079-B747340825E5", crate("crate"))]
 #[derive(Debug)]
 #[repr(C)]
 pub struct EfiProperties {
@@ -552,3 +755,73 @@ impl<'tbl> ConfigTable<'tbl> for ConformanceProfile {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::mem::{MemoryFlags, MemoryType};
+
+    #[test]
+    fn rsdp() {
+        let mut raw = [0u8; size_of::<RsdpV2>()];
+        raw[..8].copy_from_slice(&RsdpV1::SIGNATURE);
+        raw[9..15].copy_from_slice(b"NUEFI ");
+        raw[15] = 2;
+        raw[20..24].copy_from_slice(&(size_of::<RsdpV2>() as u32).to_ne_bytes());
+        raw[24..32].copy_from_slice(&0x1000u64.to_ne_bytes());
+        raw[8] = acpi_checksum(&raw[..20]).wrapping_neg();
+        raw[32] = acpi_checksum(&raw).wrapping_neg();
+
+        // Safety: `raw` is an RSDP
+        let table = unsafe { AcpiTable20::from_raw(raw.as_ptr().cast()) };
+        let rsdp = table.rsdp().unwrap();
+        assert_eq!(&rsdp.oem_id(), b"NUEFI ");
+        assert_eq!(rsdp.revision(), 2);
+        assert_eq!(rsdp.xsdt_address(), 0x1000);
+
+        raw[24] = 0xFF;
+        // Safety: `raw` is an RSDP
+        let table = unsafe { AcpiTable20::from_raw(raw.as_ptr().cast()) };
+        assert!(table.rsdp().is_none());
+    }
+
+    #[test]
+    fn memory_attributes() {
+        const DESC: usize = 48;
+        // Header, then 2 descriptors with padding
+        let mut raw = [0u64; 2 + (DESC / 8) * 2];
+        raw[0] = 1 | (2 << 32);
+        raw[1] = DESC as u64;
+        for (i, d) in raw[2..].chunks_mut(DESC / 8).enumerate() {
+            d[0] = 5 + i as u64;
+            d[1] = 0x1000 * (i as u64 + 1);
+            d[3] = 2;
+            // `MemoryFlags::RUNTIME`
+            d[4] = 1 << 63;
+        }
+
+        // Safety: `raw` is a memory attributes table
+        let table = unsafe { MemoryAttributes::from_raw(raw.as_ptr().cast()) };
+        assert_eq!(table.version(), 1);
+        assert_eq!(table.len(), 2);
+        let descs: alloc::vec::Vec<_> = table.descriptors().collect();
+        assert_eq!(descs.len(), 2);
+        assert_eq!(descs[1].ty(), MemoryType::RUNTIME_DATA);
+        assert_eq!(descs[1].start().as_u64(), 0x2000);
+        assert_eq!(descs[1].pages(), 2);
+        assert_eq!(descs[1].attribute(), MemoryFlags::RUNTIME);
+        assert!(descs[1].is_runtime());
+    }
+
+    #[test]
+    fn known_tables_sorted() {
+        for w in KNOWN_TABLES.windows(2) {
+            assert!(
+                w[0].0 < w[1].0,
+                "{} and {} are out of order",
+                w[0].1,
+                w[1].1
+            );
+        }
+    }
+}
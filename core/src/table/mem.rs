@@ -1,13 +1,83 @@
 //! UEFI Memory allocation related types
 
+/// Implement common address helpers for `$name`
+macro_rules! address {
+    ($name:ident) => {
+        impl $name {
+            #[inline]
+            pub const fn new(addr: u64) -> Self {
+                Self(addr)
+            }
+
+            #[inline]
+            pub const fn as_u64(self) -> u64 {
+                self.0
+            }
+
+            /// Offset this address by `bytes`
+            ///
+            /// # Panics
+            ///
+            /// - On overflow, in debug builds
+            #[inline]
+            pub const fn add(self, bytes: u64) -> Self {
+                Self(self.0 + bytes)
+            }
+
+            /// Round this address up to the next multiple of `align`
+            ///
+            /// # Panics
+            ///
+            /// - If `align` is not a power of two
+            /// - On overflow, in debug builds
+            #[inline]
+            pub const fn align_up(self, align: u64) -> Self {
+                assert!(align.is_power_of_two(), "align must be a power of two");
+                Self((self.0 + (align - 1)) & !(align - 1))
+            }
+
+            /// Whether this address is a multiple of `align`
+            ///
+            /// # Panics
+            ///
+            /// - If `align` is not a power of two
+            #[inline]
+            pub const fn is_aligned(self, align: u64) -> bool {
+                assert!(align.is_power_of_two(), "align must be a power of two");
+                self.0 & (align - 1) == 0
+            }
+        }
+
+        impl From<u64> for $name {
+            #[inline]
+            fn from(value: u64) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for u64 {
+            #[inline]
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
 /// UEFI Physical Address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct PhysicalAddress(u64);
 
+address!(PhysicalAddress);
+
 /// UEFI Virtual Address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct VirtualAddress(u64);
 
+address!(VirtualAddress);
+
 /// UEFI Allocation type
 #[repr(transparent)]
 pub struct AllocateType(u32);
@@ -85,6 +155,40 @@ impl MemoryType {
 
     /// Max value.
     const _MAX: Self = Self(16);
+
+    /// Whether memory of this type may be used as general purpose RAM
+    /// after [`ExitBootServices`][crate::table::BootServices::exit_boot_services]
+    ///
+    /// This is the case for loader and boot service memory,
+    /// and [`CONVENTIONAL`][`MemoryType::CONVENTIONAL`] memory.
+    ///
+    /// Everything else, such as [Runtime][`MemoryType::is_runtime`],
+    /// ACPI, and reserved memory, must be preserved.
+    ///
+    /// # Note
+    ///
+    /// Loader memory still contains whatever the loader put there,
+    /// such as this image and the memory map itself.
+    /// It is only free once the OS is done with it.
+    #[inline]
+    pub const fn is_usable_after_exit(&self) -> bool {
+        matches!(
+            *self,
+            Self::LOADER_CODE
+                | Self::LOADER_DATA
+                | Self::BOOT_CODE
+                | Self::BOOT_DATA
+                | Self::CONVENTIONAL
+        )
+    }
+
+    /// Whether memory of this type is used by Runtime Services,
+    /// and must be preserved and mapped after
+    /// [`ExitBootServices`][crate::table::BootServices::exit_boot_services]
+    #[inline]
+    pub const fn is_runtime(&self) -> bool {
+        matches!(*self, Self::RUNTIME_CODE | Self::RUNTIME_DATA)
+    }
 }
 
 /// UEFI Memory flags
@@ -111,14 +215,61 @@ impl MemoryFlags {
 }
 
 /// UEFI Memory Descriptor
+#[derive(Debug, Clone, Copy)]
 #[repr(C)]
 pub struct MemoryDescriptor {
     ty: MemoryType,
     start: PhysicalAddress,
+    virt_start: VirtualAddress,
     pages: u64,
     attribute: MemoryFlags,
 }
 
 impl MemoryDescriptor {
     pub(crate) const _VERSION: u32 = 1;
+
+    /// Size of a UEFI page
+    pub const PAGE_SIZE: u64 = 4096;
+
+    /// Type of memory in this region
+    #[inline]
+    pub const fn ty(&self) -> MemoryType {
+        self.ty
+    }
+
+    /// Physical start address of this region
+    #[inline]
+    pub const fn start(&self) -> PhysicalAddress {
+        self.start
+    }
+
+    /// Virtual start address of this region
+    #[inline]
+    pub const fn virt_start(&self) -> VirtualAddress {
+        self.virt_start
+    }
+
+    /// Set the virtual start address of this region
+    #[inline]
+    pub fn set_virt_start(&mut self, virt: VirtualAddress) {
+        self.virt_start = virt;
+    }
+
+    /// Number of [`MemoryDescriptor::PAGE_SIZE`] pages in this region
+    #[inline]
+    pub const fn pages(&self) -> u64 {
+        self.pages
+    }
+
+    /// Attributes of this region
+    #[inline]
+    pub const fn attribute(&self) -> MemoryFlags {
+        self.attribute
+    }
+
+    /// Whether this region must be mapped for Runtime Services
+    #[inline]
+    pub const fn is_runtime(&self) -> bool {
+        self.attribute.0 & MemoryFlags::RUNTIME.0 != 0
+    }
 }
@@ -36,6 +36,11 @@ struct Config {
     /// `entry(alloc_error)`
     alloc_error: bool,
 
+    /// Test mode, omitting the panic and alloc error handlers
+    ///
+    /// `entry(test)`
+    test: bool,
+
     /// Whether to generate and register a default `UefiLogger`
     ///
     /// - `entry(log)`
@@ -50,6 +55,7 @@ impl Config {
             alloc: false,
             panic: false,
             alloc_error: false,
+            test: false,
             log: None,
         }
     }
@@ -72,6 +78,9 @@ struct Log {
 
     /// Exclude these targets
     exclude: Option<Vec<String>>,
+
+    /// Also log to this file
+    file: Option<String>,
 }
 
 impl Log {
@@ -82,6 +91,7 @@ impl Log {
             all: false,
             targets: None,
             exclude: None,
+            file: None,
         }
     }
 }
@@ -176,6 +186,22 @@ fn log(i: &Ident, list: &MetaList, errors: &mut Errors, opts: &mut Config) -> bo
                                 }
                                 log.targets = Some(targets.clone());
                             }
+                        } else if i == "file" {
+                            if log.file.is_some() {
+                                errors.push(li.path.span(), "Duplicate attribute `file`");
+                            } else if li.nested.len() != 1 {
+                                errors.push(
+                                    li.span(),
+                                    r#"Attribute `file` expected one value. Try `file("\\path")`"#,
+                                );
+                            } else if let Some(NestedMeta::Lit(Lit::Str(lit))) = li.nested.first() {
+                                log.file = Some(lit.value());
+                            } else {
+                                errors.push(
+                                    li.nested.span(),
+                                    format!("Expected string, got: {:?}", li.nested),
+                                );
+                            }
                         } else {
                             errors.push(i.span(), format!("Unexpected argument `{}`", i));
                         }
@@ -232,6 +258,12 @@ fn simple_opts(i: &Ident, path: &Path, errors: &mut Errors, opts: &mut Config) -
         }
         opts.panic = true;
         true
+    } else if i == "test" {
+        if opts.test {
+            errors.push(path.span(), "Duplicate attribute `test`");
+        }
+        opts.test = true;
+        true
     } else {
         false
     }
@@ -347,7 +379,8 @@ Try `fn {}(handle: EfiHandle, table: SystemTable<Boot>) -> error::Result<()>`
 
     let krate = opts.common.krate();
 
-    let panic = if opts.panic {
+    // Test harnesses provide their own handlers
+    let panic = if opts.panic && !opts.test {
         quote! {
             const _: () = {
                 use #krate::handlers::panic;
@@ -364,7 +397,7 @@ Try `fn {}(handle: EfiHandle, table: SystemTable<Boot>) -> error::Result<()>`
         quote! {}
     };
 
-    let alloc_error = if opts.alloc_error {
+    let alloc_error = if opts.alloc_error && !opts.test {
         quote! {
             const _: () = {
                 use #krate::handlers::alloc_error;
@@ -398,9 +431,9 @@ Try `fn {}(handle: EfiHandle, table: SystemTable<Boot>) -> error::Result<()>`
         let exclude = log.exclude.unwrap_or_default();
         let targets = log.targets.unwrap_or_default();
         let color = if log.color {
-            quote! {.color();}
+            quote! {.color()}
         } else {
-            quote! {;}
+            quote! {}
         };
         let color_ty = if log.color {
             quote! {UefiColorLogger}
@@ -412,14 +445,20 @@ Try `fn {}(handle: EfiHandle, table: SystemTable<Boot>) -> error::Result<()>`
         } else {
             quote! { new(&[module_path!(), #(#targets),*]) }
         };
+        let (file, file_ty) = if let Some(file) = log.file {
+            (quote! {.file(#file)}, quote! {UefiFileLogger<#color_ty>})
+        } else {
+            (quote! {}, quote! {#color_ty})
+        };
         quote! {{
             #[allow(unused_imports)]
-            use #krate::logger::{UefiColorLogger, UefiLogger};
+            use #krate::logger::{UefiColorLogger, UefiFileLogger, UefiLogger};
             use ::core::module_path;
 
-            static NUEFI_LOGGER: #color_ty = UefiLogger::#all
+            static NUEFI_LOGGER: #file_ty = UefiLogger::#all
                 .exclude(&[#(#exclude),*])
                 #color
+                #file;
 
             UefiLogger::init(&NUEFI_LOGGER);
         }}
@@ -438,7 +477,7 @@ Try `fn {}(handle: EfiHandle, table: SystemTable<Boot>) -> error::Result<()>`
                 EfiHandle,
                 SystemTable,
                 table::Boot,
-                error,
+                error::{self, IntoEfiMain},
             };
 
             #[no_mangle]
@@ -447,7 +486,7 @@ Try `fn {}(handle: EfiHandle, table: SystemTable<Boot>) -> error::Result<()>`
             #[no_mangle]
             pub fn __internal__nuefi__main(handle: EfiHandle, table: SystemTable<Boot>) -> error::Result<()> {
                 #log
-                #ident(handle, table)
+                IntoEfiMain::into_efi_main(#ident(handle, table))
             }
         };
 
@@ -111,6 +111,10 @@ pub(crate) fn parse_guid(
 
     if let Some(guid) = &opts {
         match Uuid::parse_me(guid) {
+            Ok(guid) if guid.to_bytes() == [0; 16] => {
+                errors.push(input.span(), "Invalid GUID: GUID must not be all zero");
+                error_def
+            }
             Ok(guid) => {
                 let lol = format!("{:?}", guid.to_bytes());
                 if let Ok(lol) = syn::parse_str::<ExprArray>(&lol) {
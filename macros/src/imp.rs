@@ -1,5 +1,6 @@
 use std::fmt::Display;
 
+use proc_macro::tracked;
 use quote::{__private::Span, format_ident};
 use syn::{spanned::Spanned, Error, Ident, Lit, Meta, MetaList, NestedMeta};
 
@@ -21,8 +22,18 @@ impl CommonOpts {
     }
 
     /// Ident for our crate
+    ///
+    /// If `crate("name")` was not given, this falls back to the
+    /// `NUEFI_CRATE` environment variable, and then `nuefi`.
+    ///
+    /// The variable is tracked, so changing it recompiles users of the macro.
     pub fn krate(&self) -> Ident {
-        self.krate.clone().unwrap_or(format_ident!("nuefi"))
+        self.krate
+            .clone()
+            .unwrap_or_else(|| match tracked::env_var("NUEFI_CRATE") {
+                Ok(name) if !name.is_empty() => format_ident!("{}", name),
+                _ => format_ident!("nuefi"),
+            })
     }
 }
 
@@ -1,4 +1,5 @@
 //! Nuefi internal semver-exempt unstable macro crate
+#![feature(proc_macro_tracked_env)]
 use proc_macro::TokenStream;
 
 mod entry;
@@ -13,6 +14,10 @@ mod imp;
 /// The function must have two arguments, [`EfiHandle`][EfiHandle] and
 /// [`SystemTable<Boot>`][SystemTable], and return [`Result<()>`][Result].
 ///
+/// Any return type implementing [`IntoEfiMain`][IntoEfiMain] is accepted,
+/// such as [`Status`][Status], or a [`Result`][core::result::Result] with an
+/// error convertible to [`UefiError`][UefiError].
+///
 /// # Options
 ///
 /// This attribute accepts several options, in the form `entry(option)`,
@@ -23,6 +28,12 @@ mod imp;
 ///     Here you can see we changed `uefi` to `nuefi`, but the macro
 ///     would still use `uefi` and fail to compile.
 ///     This option solves that problem.
+///     - If not given, the `NUEFI_CRATE` environment variable is used,
+///       if set at compile time. This lets you specify a renamed crate once,
+///       such as in `.cargo/config.toml`, instead of on every macro.
+///     - Note that the variable applies to every crate in the build that
+///       uses these macros, including dependencies, so it is only suitable
+///       when all of them refer to `nuefi` by the same name.
 /// - `log`
 ///     - Whether to generate and register a default [`log`][log] global logger
 ///       using [`UefiLogger`][UefiLogger].
@@ -39,6 +50,9 @@ mod imp;
 ///         - Exclude the logging targets identified by this list.
 ///     - `color`
 ///         - Enable colorful logging
+///     - `file("\\nuefi.log")`
+///         - Also log to this file, on the filesystem your image was loaded
+///           from, using [`UefiFileLogger`][UefiFileLogger]
 /// - `panic`
 ///     - Whether to generate a `panic_impl` or leave it up to you
 /// - `alloc`
@@ -46,6 +60,10 @@ mod imp;
 /// - `alloc_error`
 ///     - Whether to generate an `alloc_error_handler` or leave it up to you.
 ///     This requires [`#![feature(alloc_error_handler)]`][alloc_err].
+/// - `test`
+///     - Test mode, for test harnesses that provide their own handlers.
+///       The entry point is generated as normal,
+///       but `panic` and `alloc_error` are ignored.
 ///
 /// # Example
 ///
@@ -128,10 +146,14 @@ mod imp;
 /// [log]: <https://crates.io/crates/log>
 /// [alloc_err]: <https://doc.rust-lang.org/nightly/unstable-book/language-features/alloc-error-handler.html>
 /// [UefiLogger]: ./logger/struct.UefiLogger.html
+/// [UefiFileLogger]: ./logger/struct.UefiFileLogger.html
 /// [SystemTable]: ./table/struct.SystemTable.html
 /// [EfiHandle]: ./struct.EfiHandle.html
 /// [Boot]: ./table/struct.Boot.html
 /// [Result]: ./error/type.Result.html
+/// [IntoEfiMain]: ./error/trait.IntoEfiMain.html
+/// [Status]: ./error/struct.Status.html
+/// [UefiError]: ./error/struct.UefiError.html
 // FIXME: Above links for docs.rs? is there a way to portably link?
 // ..just make proc macro depend on nuefi?
 // cyclic?
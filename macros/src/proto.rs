@@ -3,12 +3,17 @@ use quote::{__private::Span, quote};
 use syn::{
     ext::IdentExt,
     parse_macro_input,
+    parse_quote_spanned,
+    punctuated::Punctuated,
     spanned::Spanned,
+    Attribute,
     AttributeArgs,
     ItemStruct,
     Lit,
     Meta,
     NestedMeta,
+    Path,
+    Token,
     Type,
     TypeGroup,
     TypePath,
@@ -84,12 +89,15 @@ fn parse_args(args: &[NestedMeta], errors: &mut Errors, opts: &mut Opts) {
 
 pub fn proto(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as AttributeArgs);
-    let input = parse_macro_input!(input as ItemStruct);
+    let mut input = parse_macro_input!(input as ItemStruct);
     let mut errors: Errors = Errors::new();
     let mut opts = Opts::new();
 
     parse_args(&args, &mut errors, &mut opts);
 
+    // Replace derived `Debug` with our own impl that includes the GUID
+    let has_debug = strip_derive_debug(&mut input.attrs);
+
     let imp_struct = &input.ident;
     let imp_generics = &input.generics;
 
@@ -149,10 +157,14 @@ pub fn proto(args: TokenStream, input: TokenStream) -> TokenStream {
         }
     };
 
+    let mut imp_first_name = quote! { 0 };
+
     let imp_first_field = match &input.fields {
         syn::Fields::Named(fields) => {
             if let Some(first) = fields.named.first() {
                 let ty = &first.ty;
+                let name = &first.ident;
+                imp_first_name = quote! { #name };
                 let i = match_ty(ty, fields.named.span());
                 imp_raw_ty_ident = quote! { #i };
                 quote! { #ty }
@@ -184,6 +196,24 @@ pub fn proto(args: TokenStream, input: TokenStream) -> TokenStream {
 
     let name = imp_struct.unraw().to_string();
 
+    let debug = if has_debug {
+        quote! {
+            impl #imp_generics ::core::fmt::Debug for #imp_struct #imp_generics {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.debug_struct(#name)
+                        .field(
+                            "guid",
+                            &<Self as #krate::nuefi_core::extra::Protocol<'table>>::GUID,
+                        )
+                        .field("interface", &self.#imp_first_name)
+                        .finish()
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     let expanded = quote! {
         #input
 
@@ -197,9 +227,16 @@ pub fn proto(args: TokenStream, input: TokenStream) -> TokenStream {
 
             #[inline]
             unsafe fn from_raw(this: #imp_first_field) -> Self {
+                debug_assert!(
+                    !this.is_null(),
+                    "Tried to create Protocol \"{}\" from a null pointer",
+                    #name,
+                );
                 <#imp_struct>::new(this)
             }
         }
+
+        #debug
     };
 
     let e = if let Some(e) = errors.combine() {
@@ -213,3 +250,35 @@ pub fn proto(args: TokenStream, input: TokenStream) -> TokenStream {
         #expanded
     })
 }
+
+/// Remove `Debug` from every `#[derive(...)]` in `attrs`,
+/// keeping any other derives.
+///
+/// Returns whether `Debug` was found
+fn strip_derive_debug(attrs: &mut Vec<Attribute>) -> bool {
+    let mut found = false;
+    attrs.retain_mut(|attr| {
+        if !attr.path.is_ident("derive") {
+            return true;
+        }
+        let Ok(derives) = attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+        else {
+            return true;
+        };
+        let count = derives.len();
+        let rest: Punctuated<Path, Token![,]> = derives
+            .into_iter()
+            .filter(|p| !matches!(p.segments.last(), Some(s) if s.ident == "Debug"))
+            .collect();
+        if rest.len() == count {
+            return true;
+        }
+        found = true;
+        if rest.is_empty() {
+            return false;
+        }
+        *attr = parse_quote_spanned!(attr.span()=> #[derive(#rest)]);
+        true
+    });
+    found
+}
@@ -0,0 +1,9 @@
+//! Test that return types other than `Result<()>` are accepted
+use nuefi::{entry, error::Status, table::Boot, EfiHandle, SystemTable};
+
+#[entry]
+fn e_main(_handle: EfiHandle, _table: SystemTable<Boot>) -> Status {
+    Status::SUCCESS
+}
+
+fn main() {}
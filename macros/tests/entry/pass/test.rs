@@ -0,0 +1,10 @@
+//! Test that `test` mode omits the handlers, which would otherwise conflict
+//! with the ones `std` provides
+use nuefi::{entry, error::Result, table::Boot, EfiHandle, SystemTable};
+
+#[entry(test, panic, alloc_error)]
+fn e_main(_handle: EfiHandle, _table: SystemTable<Boot>) -> Result<()> {
+    Ok(())
+}
+
+fn main() {}
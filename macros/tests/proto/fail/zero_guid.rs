@@ -0,0 +1,30 @@
+//! Test that an all zero GUID fails
+use nuefi::Protocol;
+
+// A nil GUID is never valid for a protocol
+const GUID: &str = "00000000-0000-0000-0000-000000000000";
+
+#[repr(C)]
+struct RawProto {
+    pro: *mut RawProto,
+}
+
+#[Protocol("00000000-0000-0000-0000-000000000000")]
+#[derive(Debug)]
+#[repr(transparent)]
+struct Proto<'table> {
+    /// .
+    interface: *mut RawProto,
+    phantom: core::marker::PhantomData<&'table mut RawProto>,
+}
+
+impl<'t> Proto<'t> {
+    pub(crate) unsafe fn new(interface: *mut RawProto) -> Self {
+        Self {
+            interface,
+            phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+fn main() {}
@@ -49,6 +49,12 @@ fn main() {
     // assert_eq!(Proto::NAME, name, "Protocol macro didn't do NAME correctly");
     assert_eq!(Proto::NAME, name, "Protocol macro didn't do NAME correctly");
 
+    let dbg = format!("{:?}", p);
+    assert!(
+        dbg.starts_with("Proto { guid: ") && dbg.contains("interface: 0x0"),
+        "Protocol macro didn't do Debug correctly: {dbg}"
+    );
+
     // println!("{:?}", p.guid());
 
     // let x: Guid = unsafe {
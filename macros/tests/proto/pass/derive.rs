@@ -0,0 +1,38 @@
+//! Test that the Protocol macro keeps other derives next to `Debug`
+use core::ptr::null_mut;
+
+use nuefi::Protocol;
+
+#[repr(C)]
+struct RawProto {
+    pro: *mut RawProto,
+}
+
+#[Protocol("c986ec27-af54-4b55-80aa-91697fcdf8eb")]
+#[derive(Debug, Clone)]
+#[repr(transparent)]
+struct Proto<'table> {
+    /// .
+    interface: *mut RawProto,
+    phantom: core::marker::PhantomData<&'table mut RawProto>,
+}
+
+impl<'t> Proto<'t> {
+    pub(crate) unsafe fn new(interface: *mut RawProto) -> Self {
+        Self {
+            interface,
+            phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+fn main() {
+    let p = unsafe { Proto::new(null_mut()) };
+    let p = p.clone();
+
+    let dbg = format!("{:?}", p);
+    assert!(
+        dbg.starts_with("Proto { guid: ") && dbg.contains("interface: 0x0"),
+        "Protocol macro didn't do Debug correctly: {dbg}"
+    );
+}
@@ -62,10 +62,12 @@
 #![no_std]
 #![feature(alloc_error_handler)]
 extern crate alloc;
+#[cfg(test)]
+extern crate std;
 
 use core::{
     ffi::c_void,
-    fmt::Write,
+    fmt::{Display, Write},
     panic::PanicInfo,
     ptr::addr_of,
     sync::atomic::{AtomicPtr, Ordering},
@@ -75,10 +77,11 @@ use core::{
 use log::{error, info};
 pub use macros::{entry, Protocol, GUID};
 pub use nuefi_core::error;
+use nuefi_core::{base::Char16, table::TableKind};
+use proto::console::raw::RawSimpleTextOutput;
 use table::raw::RawSystemTable;
 
-use crate::nuefi_core::base::Status;
-pub use crate::table::{Boot, SystemTable};
+pub use crate::table::{Boot, Runtime, SystemTable};
 pub mod logger;
 pub mod mem;
 pub mod proto;
@@ -96,6 +99,8 @@ static TABLE: AtomicPtr<RawSystemTable> = AtomicPtr::new(core::ptr::null_mut());
 static HANDLE: AtomicPtr<c_void> = AtomicPtr::new(core::ptr::null_mut());
 
 pub use nuefi_core::base::Handle as EfiHandle;
+/// Commonly used [UEFI Core types][nuefi_core::base]
+pub use nuefi_core::base::{Boolean, Event, Guid, Status};
 
 /// Get the global [`SystemTable<Boot>`], if available
 fn get_boot_table() -> Option<SystemTable<Boot>> {
@@ -110,16 +115,43 @@ fn get_boot_table() -> Option<SystemTable<Boot>> {
     table.as_boot()
 }
 
+/// Get the global [`SystemTable<Runtime>`], if available
+fn get_runtime_table() -> Option<SystemTable<Runtime>> {
+    let table = TABLE.load(Ordering::Acquire);
+    if table.is_null() {
+        return None;
+    }
+    // Safety:
+    // - Table is not null
+    // - Table must be valid or else this code could not be running
+    let internal: SystemTable<table::Internal> = unsafe { SystemTable::new(table) };
+    internal.as_runtime().or_else(|| {
+        // Runtime Services are also valid during boot
+        internal.as_boot()?;
+        // Safety:
+        // - Above check verifies we are still in boot mode
+        // - `SystemTable<Runtime>` only exposes what is valid in both modes
+        Some(unsafe { SystemTable::new(table) })
+    })
+}
+
+/// Call `f` with the global [`SystemTable<Runtime>`]
+///
+/// This works both before and after ExitBootServices is called,
+/// and is intended for operations that are valid at runtime,
+/// such as variables, time, and reset.
+///
+/// Returns [`None`] if the table is not available.
+pub fn with_runtime_table<E, F: FnOnce(&SystemTable<Runtime>) -> E>(f: F) -> Option<E> {
+    get_runtime_table().map(|table| f(&table))
+}
+
 /// Get the global Image [`EfiHandle`], if available
 fn get_image_handle() -> Option<EfiHandle> {
     let _table = TABLE.load(Ordering::Acquire);
     let handle_p = HANDLE.load(Ordering::Relaxed);
-    if !handle_p.is_null() {
-        // Safety: `handle_p` was set in `efi_main` to our handle
-        unsafe { Some(EfiHandle::new(handle_p)) }
-    } else {
-        None
-    }
+    // Safety: `handle_p` was set in `efi_main` to our handle
+    unsafe { EfiHandle::new_checked(handle_p) }
 }
 
 /// UEFI Entry point
@@ -174,23 +206,38 @@ extern "efiapi" fn efi_main(image: EfiHandle, system_table: *mut RawSystemTable)
     // Safety: Unsure how it can be unsafe tbh.
     let (ext,) = unsafe {
         if addr_of!(__INTERNAL_NUEFI_YOU_MUST_USE_MACRO).is_null() {
-            return Status::INVALID_PARAMETER;
+            (None,)
+        } else {
+            (__INTERNAL_NUEFI_YOU_MUST_USE_MACRO,)
         }
-        (__INTERNAL_NUEFI_YOU_MUST_USE_MACRO,)
     };
 
-    if image.as_ptr().is_null() || system_table.is_null() || !matches!(ext, Some(false)) {
+    if image.as_ptr().is_null() || system_table.is_null() {
         return Status::INVALID_PARAMETER;
     }
 
     // Safety:
     // - Assured pointer wasn't null above
     // - Firmware assures us this is a fully valid system table
-    let valid = unsafe { RawSystemTable::validate(system_table) };
+    let valid = unsafe { RawSystemTable::check(system_table) };
     if let Err(e) = valid {
+        // An invalid System table can't be trusted to have a usable `con_out`
+        if e.table != TableKind::System {
+            // Safety: The System table itself was valid
+            unsafe { report(system_table, e) };
+        }
         return e.status();
     }
 
+    // Anything other than `Some(false)` means the `entry` macro wasn't used,
+    // and `__internal__nuefi__main` cannot be trusted.
+    if ext != Some(false) {
+        const MSG: &str = "nuefi entry point was not generated by the `#[entry]` macro";
+        // Safety: `system_table` was validated above
+        unsafe { report(system_table, MSG) };
+        return Status::INVALID_PARAMETER;
+    }
+
     // Store a copy of the pointer to the image handle and system table
     HANDLE.store(image.as_ptr(), Ordering::Relaxed);
     TABLE.store(system_table, Ordering::Release);
@@ -208,6 +255,82 @@ extern "efiapi" fn efi_main(image: EfiHandle, system_table: *mut RawSystemTable)
     }
 }
 
+/// Print `msg` and a newline to `con_out`, if there is one
+///
+/// This is for errors in [`efi_main`], before any logger exists.
+/// The global table isn't set yet either, so allocation would fail.
+/// Instead `msg` is encoded into a fixed stack buffer and passed straight to
+/// firmware.
+///
+/// # Safety
+///
+/// - `system_table` must be a valid System table
+unsafe fn report(system_table: *mut RawSystemTable, msg: impl Display) {
+    type OutputString =
+        unsafe extern "efiapi" fn(*mut RawSimpleTextOutput, *const Char16) -> Status;
+
+    /// [`Write`] to `con_out`, in chunks of `buf`
+    struct Out {
+        this: *mut RawSimpleTextOutput,
+        output_string: OutputString,
+        buf: [Char16; 128],
+        len: usize,
+    }
+
+    impl Out {
+        fn push(&mut self, c: Char16) {
+            // Leave room for the nul
+            if self.len == self.buf.len() - 1 {
+                self.flush();
+            }
+            self.buf[self.len] = c;
+            self.len += 1;
+        }
+
+        fn flush(&mut self) {
+            if self.len == 0 {
+                return;
+            }
+            self.buf[self.len] = 0;
+            // Safety:
+            // - `buf` is nul terminated
+            // - `report`s caller ensures `this` is valid
+            let _ = unsafe { (self.output_string)(self.this, self.buf.as_ptr()) };
+            self.len = 0;
+        }
+    }
+
+    impl Write for Out {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            for c in s.encode_utf16() {
+                if c == u16::from(b'\n') {
+                    self.push(u16::from(b'\r'));
+                }
+                self.push(c);
+            }
+            Ok(())
+        }
+    }
+
+    // Safety: Caller ensures `system_table` is valid
+    let this: *mut RawSimpleTextOutput = unsafe { (*system_table).con_out.cast() };
+    if this.is_null() {
+        return;
+    }
+    // Safety: `this` isn't null, and a valid System table has a valid `con_out`
+    let Some(output_string) = (unsafe { (*this).output_string }) else {
+        return;
+    };
+    let mut out = Out {
+        this,
+        output_string,
+        buf: [0; 128],
+        len: 0,
+    };
+    let _ = writeln!(out, "{msg}");
+    out.flush();
+}
+
 #[doc(hidden)]
 pub mod handlers;
 
@@ -473,11 +596,25 @@ mod tests {
 
     const IMAGE: EfiHandle = unsafe { EfiHandle::new(69420 as *mut _) };
 
+    /// Held by tests that set the global [`TABLE`]
+    pub(crate) static GLOBAL_TABLE: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Mock boot services with every function missing, except those set by `f`
+    pub(crate) fn mock_boot(
+        f: impl FnOnce(&mut table::raw::RawBootServices),
+    ) -> table::raw::RawBootServices {
+        // Safety: All fields of `RawBootServices` are safely nullable/zero
+        let mut boot = unsafe { core::mem::zeroed() };
+        f(&mut boot);
+        boot
+    }
+
     /// This test sets up a mock UEFI environment for the purposes of running
     /// our wrappers and unsafe code through MIRI where possible, in as
     /// close an environment to reality as possible.
     #[test]
     fn miri() -> Result<()> {
+        let _lock = GLOBAL_TABLE.lock().unwrap_or_else(|e| e.into_inner());
         // setup();
         let (mut st, _box) = { mock() };
         {
@@ -527,6 +664,45 @@ mod tests {
             // See: The commit that added this comment for details
             // forget(_box);
         }
+        // Don't leave the global table dangling for other tests
+        TABLE.store(core::ptr::null_mut(), Ordering::Release);
         Ok(())
     }
+
+    /// Everything written by [`capture_string`]
+    static CAPTURED: std::sync::Mutex<Vec<Char16>> = std::sync::Mutex::new(Vec::new());
+
+    unsafe extern "efiapi" fn capture_string(
+        _this: *mut RawSimpleTextOutput,
+        string: *const Char16,
+    ) -> Status {
+        let mut out = CAPTURED.lock().unwrap();
+        let mut i = 0;
+        // Safety: `string` is nul terminated
+        unsafe {
+            while *string.add(i) != 0 {
+                out.push(*string.add(i));
+                i += 1;
+            }
+        }
+        Status::SUCCESS
+    }
+
+    /// [`report`] has to work with more than one buffer of output
+    #[test]
+    fn report_long() {
+        // Safety: All fields of `RawSimpleTextOutput` are safely nullable/zero
+        let mut out: RawSimpleTextOutput = unsafe { core::mem::zeroed() };
+        out.output_string = Some(capture_string);
+        // Safety: All fields of `RawSystemTable` are safely nullable/zero
+        let mut system: RawSystemTable = unsafe { core::mem::zeroed() };
+        system.con_out = core::ptr::addr_of_mut!(out).cast();
+
+        let msg = "nuefi ".repeat(50);
+        // Safety: `system` has a valid `con_out`
+        unsafe { report(&mut system, &msg) };
+
+        let expected: Vec<Char16> = msg.encode_utf16().chain([0xD, 0xA]).collect();
+        assert_eq!(*CAPTURED.lock().unwrap(), expected);
+    }
 }
@@ -1,11 +1,18 @@
 //! Logging helpers for UEFI
-use core::fmt::Write;
+use alloc::{format, vec::Vec};
+use core::{cell::RefCell, fmt::Write, mem::transmute};
 
 use log::{Log, Metadata, Record};
 
 use crate::{
+    error::{Result, Status},
     get_boot_table,
-    proto::console::{TextBackground, TextForeground},
+    get_image_handle,
+    proto::{
+        console::{TextBackground, TextForeground},
+        loaded_image::LoadedImage,
+        media::{FsHandle, SimpleFileSystem},
+    },
 };
 
 /// UEFI [Log][log::Log] implementation
@@ -60,6 +67,13 @@ impl UefiLogger {
         UefiColorLogger(self)
     }
 
+    /// Also log to the file at `path`
+    ///
+    /// See [`UefiFileLogger`]
+    pub const fn file(self, path: &'static str) -> UefiFileLogger<Self> {
+        UefiFileLogger::new(self, path)
+    }
+
     /// Initialize the logger with [log]
     ///
     /// This will set the max log level to [`log::STATIC_MAX_LEVEL`]
@@ -123,6 +137,15 @@ impl Log for UefiLogger {
 /// See [`UefiLogger::color`]
 pub struct UefiColorLogger(UefiLogger);
 
+impl UefiColorLogger {
+    /// Also log to the file at `path`
+    ///
+    /// See [`UefiFileLogger`]
+    pub const fn file(self, path: &'static str) -> UefiFileLogger<Self> {
+        UefiFileLogger::new(self, path)
+    }
+}
+
 impl Log for UefiColorLogger {
     #[inline]
     fn enabled(&self, metadata: &Metadata) -> bool {
@@ -151,3 +174,168 @@ impl Log for UefiColorLogger {
         self.0.flush()
     }
 }
+
+/// Maximum amount of log data buffered while the log file can't be opened
+const FILE_BUFFER_MAX: usize = 64 * 1024;
+
+/// Written in place of records dropped because the buffer was full
+const DROPPED_MARKER: &[u8] = b"[nuefi] Log buffer full, records dropped\n";
+
+/// State for [`UefiFileLogger`]
+struct FileState {
+    /// The log file, once opened
+    file: Option<FsHandle<'static, 'static>>,
+
+    /// Records not yet written to `file`
+    buf: Vec<u8>,
+
+    /// Whether opening `file` failed, in which case it is only retried on
+    /// [`Log::flush`]
+    failed: bool,
+
+    /// Whether records have been dropped because `buf` was full,
+    /// and a marker for that has been added to it
+    dropped: bool,
+}
+
+/// Like its inner logger, but additionally appends records to a file on the
+/// filesystem our image was loaded from.
+///
+/// The file is created if it does not exist.
+///
+/// Records are buffered until the file can be opened,
+/// and flushed after every write.
+/// If opening the file fails, it is only retried on [`Log::flush`].
+/// If the buffer fills up, further records are dropped,
+/// and a marker noting this is written in their place.
+///
+/// If `ExitBootServices` has been called, this does nothing.
+///
+/// See [`UefiLogger::file`]
+pub struct UefiFileLogger<L> {
+    inner: L,
+
+    /// UEFI path to the log file, using `\`
+    path: &'static str,
+
+    state: RefCell<FileState>,
+}
+
+impl<L: Log> UefiFileLogger<L> {
+    /// Create a new [UefiFileLogger], logging to `inner` and the file at
+    /// `path`
+    pub const fn new(inner: L, path: &'static str) -> Self {
+        Self {
+            inner,
+            path,
+            state: RefCell::new(FileState {
+                file: None,
+                buf: Vec::new(),
+                failed: false,
+                dropped: false,
+            }),
+        }
+    }
+
+    /// Open, or create, the log file at `path` on our image's filesystem
+    fn open(path: &str) -> Result<FsHandle<'static, 'static>> {
+        let table = get_boot_table().ok_or(Status::NOT_READY)?;
+        let image = get_image_handle().ok_or(Status::NOT_READY)?;
+        let boot = table.boot();
+
+        // Safety: These protocols are only used within this function
+        let dev = unsafe { boot.handle_protocol::<LoadedImage>(image)? }
+            .and_then(|img| img.device())
+            .ok_or(Status::NOT_FOUND)?;
+        // Safety: See above
+        let fs =
+            unsafe { boot.handle_protocol::<SimpleFileSystem>(dev)? }.ok_or(Status::UNSUPPORTED)?;
+
+        let root = fs.open_volume()?;
+        let file = root.create(path)?;
+        file.set_position(u64::MAX)?;
+
+        // Safety: This is required because our local table is an implementation detail
+        // `FsHandle` is independent of whatever created it, and is only used
+        // while boot services are available
+        let file = unsafe { transmute::<FsHandle<'_, '_>, FsHandle<'static, 'static>>(file) };
+        Ok(file)
+    }
+
+    /// Write out as much of the buffer as possible
+    ///
+    /// If opening the file previously failed, it is only retried if `retry`
+    fn write_buf(&self, state: &mut FileState, retry: bool) {
+        if state.file.is_none() {
+            if state.failed && !retry {
+                return;
+            }
+            match Self::open(self.path) {
+                Ok(file) => {
+                    state.file = Some(file);
+                    state.failed = false;
+                }
+                Err(_) => {
+                    state.failed = true;
+                    return;
+                }
+            }
+        }
+        if let Some(file) = &state.file {
+            if let Ok(n) = file.write(&state.buf) {
+                state.buf.drain(..n);
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+impl<L: Log> Log for UefiFileLogger<L> {
+    #[inline]
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        self.inner.log(record);
+        if get_boot_table().is_none() || !self.enabled(record.metadata()) {
+            return;
+        }
+        // Opening and writing the file may log, ignore those records here
+        let Ok(mut state) = self.state.try_borrow_mut() else {
+            return;
+        };
+        if state.buf.len() < FILE_BUFFER_MAX {
+            let line = format!(
+                "[{} - {}:{}] {} - {}\n",
+                record.target(),
+                record.file().unwrap_or_default(),
+                record.line().unwrap_or_default(),
+                record.level(),
+                record.args()
+            );
+            state.buf.extend_from_slice(line.as_bytes());
+            state.dropped = false;
+        } else if !state.dropped {
+            state.buf.extend_from_slice(DROPPED_MARKER);
+            state.dropped = true;
+        }
+        self.write_buf(&mut state, false);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+        if get_boot_table().is_none() {
+            return;
+        }
+        if let Ok(mut state) = self.state.try_borrow_mut() {
+            self.write_buf(&mut state, true);
+        }
+    }
+}
+
+// Safety: Synchronized by UEFI? UEFI has one thread, and we're it.
+unsafe impl<L: Send> Send for UefiFileLogger<L> {}
+
+// Safety: Synchronized by UEFI? UEFI has one thread, and we're it.
+unsafe impl<L: Sync> Sync for UefiFileLogger<L> {}
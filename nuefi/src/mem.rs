@@ -1,14 +1,30 @@
 //! UEFI Boot time allocator
+use alloc::vec::Vec;
+#[cfg(feature = "alloc_stats")]
+use core::sync::atomic::{AtomicUsize, Ordering};
 use core::{
     alloc::{GlobalAlloc, Layout},
-    ptr::null_mut,
+    ffi::c_void,
+    ptr::{null_mut, NonNull},
 };
 
-use crate::get_boot_table;
+use crate::{
+    error::{Result, Status},
+    get_boot_table,
+    table::BootServices,
+};
 
 /// UEFI always aligns to 8.
 const POOL_ALIGN: usize = 8;
 
+/// Total bytes allocated by [`UefiAlloc`]
+#[cfg(feature = "alloc_stats")]
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+/// Total bytes freed by [`UefiAlloc`]
+#[cfg(feature = "alloc_stats")]
+static FREED: AtomicUsize = AtomicUsize::new(0);
+
 pub use nuefi_core::table::mem::{
     AllocateType,
     MemoryDescriptor,
@@ -18,6 +34,135 @@ pub use nuefi_core::table::mem::{
     VirtualAddress,
 };
 
+/// The UEFI memory map, from [`BootServices::memory_map`][memory_map]
+///
+/// This owns its buffer, allocated in [`MemoryType::LOADER_DATA`],
+/// and is safe to keep across ExitBootServices.
+///
+/// On drop the buffer is freed if Boot Services are still available,
+/// otherwise it is leaked, as the memory is then ours anyway.
+///
+/// [memory_map]: crate::table::BootServices::memory_map
+#[derive(Debug)]
+pub struct MemoryMap {
+    buf: NonNull<c_void>,
+    size: usize,
+    entry_size: usize,
+    key: usize,
+    version: u32,
+}
+
+impl MemoryMap {
+    /// # Safety
+    ///
+    /// - `buf` must have been allocated by
+    ///   [`BootServices::allocate_pool`][allocate_pool]
+    /// - `buf` must be valid for `size` bytes, containing a memory map
+    ///   with entries of `entry_size` bytes
+    /// - `entry_size` must be at least [`size_of::<MemoryDescriptor>()`]
+    ///
+    /// [allocate_pool]: crate::table::BootServices::allocate_pool
+    pub(crate) unsafe fn new(
+        buf: NonNull<c_void>,
+        size: usize,
+        entry_size: usize,
+        key: usize,
+        version: u32,
+    ) -> Self {
+        Self {
+            buf,
+            size,
+            entry_size,
+            key,
+            version,
+        }
+    }
+
+    /// Key identifying this memory map, for ExitBootServices
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Version of the [`MemoryDescriptor`]s in the map
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Size of each entry in bytes.
+    ///
+    /// This may be larger than [`size_of::<MemoryDescriptor>()`]
+    pub fn entry_size(&self) -> usize {
+        self.entry_size
+    }
+
+    /// Number of entries in the map
+    pub fn len(&self) -> usize {
+        self.size / self.entry_size
+    }
+
+    /// Whether the map has no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the entry at `index`, or [`None`] if out of bounds
+    pub fn get(&self, index: usize) -> Option<MemoryDescriptor> {
+        if index >= self.len() {
+            return None;
+        }
+        // Safety:
+        // - Construction ensures `buf` is valid for `len` entries
+        // - Entries may not be aligned for `MemoryDescriptor`
+        unsafe {
+            let ptr = self.buf.as_ptr().cast::<u8>().add(index * self.entry_size);
+            Some(ptr.cast::<MemoryDescriptor>().read_unaligned())
+        }
+    }
+
+    /// Iterator over the entries in the map
+    pub fn iter(&self) -> impl Iterator<Item = MemoryDescriptor> + '_ {
+        (0..self.len()).filter_map(|i| self.get(i))
+    }
+}
+
+impl Drop for MemoryMap {
+    fn drop(&mut self) {
+        // After ExitBootServices Boot Services are gone,
+        // and the memory is ours, so there is nothing to free.
+        if let Some(table) = get_boot_table() {
+            // Safety: Construction ensures `buf` came from `allocate_pool`
+            let _ = unsafe { table.boot().free_pool(self.buf.as_ptr()) };
+        }
+    }
+}
+
+/// Build a virtual memory map for `SetVirtualAddressMap` from the memory
+/// map `map`
+///
+/// Only regions with the [`MemoryFlags::RUNTIME`] attribute are included,
+/// each mapped at its physical address offset by `base`.
+///
+/// Returns [`Status::INVALID_PARAMETER`] if any region would overflow the
+/// address space.
+pub fn runtime_virtual_map(map: &[MemoryDescriptor], base: u64) -> Result<Vec<MemoryDescriptor>> {
+    map.iter()
+        .filter(|d| d.is_runtime())
+        .map(|d| {
+            let size = d.pages().checked_mul(MemoryDescriptor::PAGE_SIZE);
+            let virt = d.start().as_u64().checked_add(base);
+            // The end of the region must fit too
+            let end = virt.zip(size).and_then(|(v, s)| v.checked_add(s));
+            let (Some(virt), Some(_)) = (virt, end) else {
+                return Err(Status::INVALID_PARAMETER.into());
+            };
+
+            let mut d = *d;
+            d.set_virt_start(VirtualAddress::new(virt));
+            Ok(d)
+        })
+        .collect()
+}
+
 /// A UEFI memory allocator
 ///
 /// Relies on [`BootServices::allocate_pool`][allocate_pool]
@@ -37,6 +182,45 @@ impl UefiAlloc {
     pub const fn new() -> Self {
         Self { _priv: () }
     }
+
+    /// Statistics for all allocations made through [`UefiAlloc`]
+    ///
+    /// Requires the `alloc_stats` feature
+    #[cfg(feature = "alloc_stats")]
+    pub fn stats() -> AllocStats {
+        AllocStats {
+            allocated: ALLOCATED.load(Ordering::Relaxed),
+            freed: FREED.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Allocation statistics for [`UefiAlloc`]
+///
+/// See [`UefiAlloc::stats`]
+#[cfg(feature = "alloc_stats")]
+#[derive(Debug, Clone, Copy)]
+pub struct AllocStats {
+    allocated: usize,
+    freed: usize,
+}
+
+#[cfg(feature = "alloc_stats")]
+impl AllocStats {
+    /// Total bytes allocated
+    pub fn allocated(&self) -> usize {
+        self.allocated
+    }
+
+    /// Total bytes freed
+    pub fn freed(&self) -> usize {
+        self.freed
+    }
+
+    /// Bytes currently allocated
+    pub fn outstanding(&self) -> usize {
+        self.allocated.saturating_sub(self.freed)
+    }
 }
 
 // Safety: We adhere to the contract of GlobalAlloc
@@ -56,24 +240,19 @@ unsafe impl GlobalAlloc for UefiAlloc {
         } else {
             0
         };
-        let size = size + offset;
+        let Some(size) = size.checked_add(offset) else {
+            return null_mut();
+        };
 
-        if let Some(table) = get_boot_table() {
-            let ret = table.boot().allocate_pool(MemoryType::LOADER_DATA, size);
-            if let Ok(ptr) = ret {
-                let ptr = ptr.as_ptr();
-                // trace!(
-                //     "Old pointer {ptr:p} vs new pointer {:p} (aligned: {})",
-                //     ptr.add(offset),
-                //     ptr as usize & (offset.saturating_sub(1)) == 0
-                // );
-                ptr.add(offset).cast()
-            } else {
-                null_mut()
-            }
-        } else {
-            null_mut()
+        let ptr = match get_boot_table() {
+            Some(table) => alloc_in(&table.boot(), size, offset),
+            None => null_mut(),
+        };
+        #[cfg(feature = "alloc_stats")]
+        if !ptr.is_null() {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
         }
+        ptr
     }
 
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
@@ -100,10 +279,88 @@ unsafe impl GlobalAlloc for UefiAlloc {
             if let Err(e) = ret {
                 // error!("Error {e} while deallocating memory {ptr:p} with
                 // layout {layout:?}");
+            } else {
+                #[cfg(feature = "alloc_stats")]
+                FREED.fetch_add(layout.size(), Ordering::Relaxed);
             }
         }
     }
 }
 
+/// Allocate `size` bytes from `boot`, returning the pointer `offset` bytes
+/// into the allocation, or null on failure.
+///
+/// # Safety
+///
+/// - `offset` must be at most `size`
+unsafe fn alloc_in(boot: &BootServices, size: usize, offset: usize) -> *mut u8 {
+    match boot.allocate_pool(MemoryType::LOADER_DATA, size) {
+        // Safety: Caller ensures `offset` is within the allocation
+        Ok(ptr) => unsafe { ptr.as_ptr().cast::<u8>().add(offset) },
+        Err(_) => null_mut(),
+    }
+}
+
 // Safety: Synchronized by UEFI? UEFI has one thread, and we're it.
 unsafe impl Sync for UefiAlloc {}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        mem::zeroed,
+        sync::atomic::Ordering,
+    };
+
+    use super::*;
+    use crate::{
+        table::raw::RawSystemTable,
+        tests::{mock_boot, GLOBAL_TABLE},
+        TABLE,
+    };
+
+    /// Always fails with [`Status::OUT_OF_RESOURCES`]
+    unsafe extern "efiapi" fn fail_pool(
+        _ty: MemoryType,
+        _size: usize,
+        out: *mut *mut c_void,
+    ) -> Status {
+        out.write(null_mut());
+        Status::OUT_OF_RESOURCES
+    }
+
+    /// Buggy firmware that succeeds with a null pointer
+    unsafe extern "efiapi" fn null_pool(
+        _ty: MemoryType,
+        _size: usize,
+        out: *mut *mut c_void,
+    ) -> Status {
+        out.write(null_mut());
+        Status::SUCCESS
+    }
+
+    #[test]
+    fn alloc_failure_is_null() {
+        let _lock = GLOBAL_TABLE.lock().unwrap_or_else(|e| e.into_inner());
+        for pool in [fail_pool as _, null_pool as _] {
+            let mut boot = mock_boot(|b| b.allocate_pool = Some(pool));
+            // Safety: All fields of `RawSystemTable` are safely nullable/zero
+            let mut system: RawSystemTable = unsafe { zeroed() };
+            system.boot_services = &mut boot;
+            TABLE.store(&mut system, Ordering::Release);
+
+            let alloc = UefiAlloc::new();
+            // The largest possible allocation with an offset
+            let huge = isize::MAX as usize - 63;
+            for layout in [
+                Layout::new::<u64>(),
+                Layout::from_size_align(64, 64).unwrap(),
+                Layout::from_size_align(huge, 64).unwrap(),
+            ] {
+                // Safety: `layout` has a non-zero size
+                let ptr = unsafe { alloc.alloc(layout) };
+                assert!(ptr.is_null(), "{layout:?} did not fail");
+            }
+            TABLE.store(null_mut(), Ordering::Release);
+        }
+    }
+}
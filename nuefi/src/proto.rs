@@ -42,15 +42,21 @@
 
 use core::{marker::PhantomData, ops::Deref};
 
-use crate::{get_boot_table, EfiHandle};
+use crate::{error::Result, get_boot_table, table::BootServices, EfiHandle};
 
+pub mod component_name;
 pub mod console;
 pub mod device_path;
 pub mod edid;
 pub mod graphics;
+pub mod guids;
 pub mod loaded_image;
 pub mod media;
 pub mod platform_init;
+pub mod pxe;
+pub mod shell;
+pub mod timestamp;
+pub mod unicode_collation;
 pub mod vendor;
 
 /// A scope around a [Protocol] that will call
@@ -83,6 +89,20 @@ impl<'table, Proto: Protocol<'table>> Scope<'table, Proto> {
     /// Close this protocol
     pub fn close(self) {}
 
+    /// Close this protocol now, reporting any errors
+    ///
+    /// Unlike [`Scope::close`] and [Drop], errors from
+    /// [`crate::table::BootServices::close_protocol`] are returned
+    /// instead of being silently discarded.
+    ///
+    /// On error the protocol may still be open, and it will not be closed
+    /// again.
+    pub fn close_now(self, boot: &BootServices<'_>) -> Result<()> {
+        let (handle, agent, controller) = (self.handle, self.agent, self.controller);
+        core::mem::forget(self);
+        boot.close_protocol::<Proto>(handle, agent, controller)
+    }
+
     /// "Leak" this Protocol
     ///
     /// It can be closed by calling
@@ -150,5 +170,5 @@ pub struct Time {
 
 pub use crate::nuefi_core::{
     base::Guid,
-    extra::{Entity, Protocol},
+    extra::{DynEntity, Entity, Protocol},
 };
@@ -0,0 +1,116 @@
+//! UEFI Component Name 2 Protocol
+use alloc::{string::String, vec::Vec};
+use core::{ffi::CStr, ptr::null};
+
+use raw::*;
+
+use crate::{
+    error::{Result, Status},
+    nuefi_core::base::decode_ucs2,
+    proto::{Guid, Protocol},
+    string::UefiStr,
+    util::interface,
+    EfiHandle,
+    Protocol,
+};
+
+pub mod raw;
+
+interface!(
+    /// UEFI Component Name 2 protocol
+    ///
+    /// Provides human readable names for drivers,
+    /// and the controllers they manage.
+    #[Protocol("6A7A5CFF-E8D9-4F70-BADA-75AB3025CE14", crate("crate"))]
+    ComponentName2(RawComponentName2)
+);
+
+impl<'table> ComponentName2<'table> {
+    /// The RFC 4646 language codes this driver supports,
+    /// separated by `;`, or [`None`] if unavailable.
+    ///
+    /// For example, `en-US;fr`
+    pub fn supported_languages(&self) -> Option<&str> {
+        let langs = self.interface().supported_languages;
+        if langs.is_null() {
+            return None;
+        }
+        // Safety: Firmware ensures this is a nul terminated ASCII string,
+        // checked for null above
+        unsafe { CStr::from_ptr(langs.cast()) }.to_str().ok()
+    }
+
+    /// The name of the driver in the RFC 4646 `language`, such as `en`
+    ///
+    /// Returns [`Status::UNSUPPORTED`] if `language` is not supported
+    pub fn driver_name(&self, language: &str) -> Result<String> {
+        let gd = self
+            .interface()
+            .get_driver_name
+            .ok_or(Status::UNSUPPORTED)?;
+        let lang = language_code(language)?;
+        let mut out = null();
+
+        // Safety: Construction ensures these are valid, `lang` is nul
+        // terminated
+        let out = unsafe { (gd)(self.interface, lang.as_ptr(), &mut out) }.map_success(|| out)?;
+        name(out)
+    }
+
+    /// The name of `controller` in the RFC 4646 `language`, such as `en`
+    ///
+    /// If `child` is given, the name of that child of `controller`
+    /// is returned instead.
+    ///
+    /// Returns [`Status::UNSUPPORTED`] if `language` is not supported,
+    /// or this driver is not managing `controller`
+    pub fn controller_name(
+        &self,
+        controller: EfiHandle,
+        child: Option<EfiHandle>,
+        language: &str,
+    ) -> Result<String> {
+        let gc = self
+            .interface()
+            .get_controller_name
+            .ok_or(Status::UNSUPPORTED)?;
+        let lang = language_code(language)?;
+        let mut out = null();
+
+        // Safety: Construction ensures these are valid, `lang` is nul
+        // terminated
+        let out = unsafe {
+            (gc)(
+                self.interface,
+                controller,
+                child.unwrap_or(EfiHandle::null()),
+                lang.as_ptr(),
+                &mut out,
+            )
+        }
+        .map_success(|| out)?;
+        name(out)
+    }
+}
+
+/// Convert `language` to a nul terminated ASCII string
+fn language_code(language: &str) -> Result<Vec<u8>> {
+    if !language.is_ascii() || language.contains('\0') {
+        return Err(Status::INVALID_PARAMETER.into());
+    }
+    let mut lang = Vec::with_capacity(language.len() + 1);
+    lang.extend_from_slice(language.as_bytes());
+    lang.push(0);
+    Ok(lang)
+}
+
+/// Copy the driver owned name `out`
+fn name(out: *const u16) -> Result<String> {
+    if out.is_null() {
+        return Err(Status::DEVICE_ERROR.into());
+    }
+    // Safety: Firmware ensures this is a nul terminated string,
+    // which is owned by the driver and must not be freed.
+    let name = unsafe { UefiStr::from_ptr(out.cast_mut()) };
+    Ok(decode_ucs2(name.as_slice()))
+}
@@ -0,0 +1,31 @@
+//! Raw UEFI Component Name 2 Protocol types
+use crate::{
+    nuefi_core::base::{Char16, Char8, Status},
+    EfiHandle,
+};
+
+pub type GetDriverName = unsafe extern "efiapi" fn(
+    this: *mut RawComponentName2,
+    language: *const Char8,
+    driver_name: *mut *const Char16,
+) -> Status;
+
+pub type GetControllerName = unsafe extern "efiapi" fn(
+    this: *mut RawComponentName2,
+    controller: EfiHandle,
+    child: EfiHandle,
+    language: *const Char8,
+    controller_name: *mut *const Char16,
+) -> Status;
+
+/// UEFI Component Name 2 protocol
+#[derive(Debug)]
+#[repr(C)]
+pub struct RawComponentName2 {
+    pub get_driver_name: Option<GetDriverName>,
+    pub get_controller_name: Option<GetControllerName>,
+
+    /// Nul terminated ASCII list of RFC 4646 language codes,
+    /// separated by `;`
+    pub supported_languages: *const Char8,
+}
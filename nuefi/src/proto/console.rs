@@ -1,19 +1,28 @@
 //! UEFI Console related protocols
+use alloc::vec::Vec;
 use core::{
     fmt::{self, Write},
     mem::size_of,
+    ptr::null_mut,
     slice::from_raw_parts_mut,
+    sync::atomic::{AtomicI32, AtomicPtr, AtomicUsize, Ordering},
 };
 
 use crate::{
     error::{Result, Status},
-    string::UefiString,
+    nuefi_core::base::{encode_ucs2, Event},
     util::interface,
 };
 
 pub mod raw;
 
-use raw::RawSimpleTextOutput;
+use raw::{
+    RawAbsolutePointer,
+    RawAbsolutePointerMode,
+    RawAbsolutePointerState,
+    RawSimpleTextOutput,
+    RawTextMode,
+};
 
 use crate::Protocol;
 
@@ -64,6 +73,56 @@ impl TextBackground {
     pub const LIGHT_GRAY: Self = Self(0x07);
 }
 
+/// Cached [`SimpleTextOutput::current_size`]
+///
+/// [`SimpleTextOutput`] is recreated every time it's retrieved,
+/// so the cache can't live in it.
+/// It only holds the size of one mode of one output, and UEFI is single
+/// threaded, so a set of atomics is enough.
+struct SizeCache {
+    /// Output the size is for, or null if nothing is cached
+    out: AtomicPtr<RawSimpleTextOutput>,
+    mode: AtomicI32,
+    cols: AtomicUsize,
+    rows: AtomicUsize,
+}
+
+impl SizeCache {
+    const fn new() -> Self {
+        Self {
+            out: AtomicPtr::new(null_mut()),
+            mode: AtomicI32::new(0),
+            cols: AtomicUsize::new(0),
+            rows: AtomicUsize::new(0),
+        }
+    }
+
+    fn get(&self, out: *mut RawSimpleTextOutput, mode: i32) -> Option<(usize, usize)> {
+        if self.out.load(Ordering::Acquire) != out || self.mode.load(Ordering::Relaxed) != mode {
+            return None;
+        }
+        Some((
+            self.cols.load(Ordering::Relaxed),
+            self.rows.load(Ordering::Relaxed),
+        ))
+    }
+
+    fn set(&self, out: *mut RawSimpleTextOutput, mode: i32, (cols, rows): (usize, usize)) {
+        self.mode.store(mode, Ordering::Relaxed);
+        self.cols.store(cols, Ordering::Relaxed);
+        self.rows.store(rows, Ordering::Relaxed);
+        self.out.store(out, Ordering::Release);
+    }
+
+    fn invalidate(&self, out: *mut RawSimpleTextOutput) {
+        let _ = self
+            .out
+            .compare_exchange(out, null_mut(), Ordering::AcqRel, Ordering::Relaxed);
+    }
+}
+
+static SIZE_CACHE: SizeCache = SizeCache::new();
+
 // interface!(SimpleTextInput(RawSimpleTextInput));
 
 interface!(
@@ -72,10 +131,13 @@ interface!(
 );
 
 impl<'table> SimpleTextOutput<'table> {
+    /// Write `string` to the output device
+    ///
+    /// Output stops at the first nul in `string`, if any.
     #[track_caller]
     pub fn output_string(&self, string: &str) -> Result<()> {
         let out = self.interface().output_string.ok_or(Status::UNSUPPORTED)?;
-        let s = UefiString::new(string);
+        let s = encode_ucs2(string);
         // Safety: s is a nul terminated string
         unsafe { out(self.interface, s.as_ptr()) }.into()
     }
@@ -114,9 +176,13 @@ impl<'table> SimpleTextOutput<'table> {
     }
 
     /// Current text attributes
+    ///
+    /// If firmware did not provide a mode, this is
+    /// [`TextForeground::WHITE`] on [`TextBackground::BLACK`]
     pub fn attributes(&self) -> (TextForeground, TextBackground) {
-        // Safety: Construction ensures these are valid
-        let mode = unsafe { *self.interface().mode };
+        let Some(mode) = self.raw_mode() else {
+            return (TextForeground::WHITE, TextBackground::BLACK);
+        };
         let attr = mode.attribute as u8;
         let f = attr & 0xF;
         let b = attr >> 4;
@@ -139,11 +205,25 @@ impl<'table> SimpleTextOutput<'table> {
     ///
     /// Clears the screen, resets cursor position.
     pub fn reset(&self) -> Result<()> {
+        SIZE_CACHE.invalidate(self.interface);
         // Safety: Construction ensures these are valid
         unsafe { (self.interface().reset.ok_or(Status::UNSUPPORTED)?)(self.interface, false) }
             .into()
     }
 
+    /// Reset the device associated with this protocol,
+    /// with extended verification.
+    ///
+    /// The firmware may run an exhaustive self-test of the device,
+    /// which can be slow.
+    /// Prefer [`SimpleTextOutput::reset`], and only use this to recover a
+    /// console that has stopped responding.
+    pub fn reset_extended(&self) -> Result<()> {
+        SIZE_CACHE.invalidate(self.interface);
+        // Safety: Construction ensures these are valid
+        unsafe { (self.interface().reset.ok_or(Status::UNSUPPORTED)?)(self.interface, true) }.into()
+    }
+
     /// Clears the screen, resets cursor position.
     pub fn clear(&self) -> Result<()> {
         // Safety: Construction ensures these are valid
@@ -171,6 +251,7 @@ impl<'table> SimpleTextOutput<'table> {
 
     /// Set the terminal mode to number `mode`
     pub fn set_mode(&self, mode: u32) -> Result<()> {
+        SIZE_CACHE.invalidate(self.interface);
         // Safety: Construction ensures these are valid
         unsafe {
             (self.interface().set_mode.ok_or(Status::UNSUPPORTED)?)(self.interface, mode as usize)
@@ -191,20 +272,15 @@ impl<'table> SimpleTextOutput<'table> {
         let mut cols = 0;
         let mut rows = 0;
         // Safety: Construction ensures these are valid
-        let ret = unsafe {
+        unsafe {
             (self.interface().query_mode.ok_or(Status::UNSUPPORTED)?)(
                 self.interface,
                 mode as usize,
                 &mut cols,
                 &mut rows,
             )
-        };
-        if ret.is_success() {
-            let mode = TextMode::new(mode, (cols, rows));
-            Ok(mode)
-        } else {
-            Err(ret.into())
         }
+        .map_success(|| TextMode::new(mode, (cols, rows)))
     }
 
     /// Terminal output modes
@@ -220,17 +296,99 @@ impl<'table> SimpleTextOutput<'table> {
         })
     }
 
+    /// Like [`SimpleTextOutput::modes`], but skips modes whose
+    /// [`TextMode::size`] matches an earlier mode
+    ///
+    /// Some firmware reports the same size under multiple mode numbers.
+    pub fn unique_modes(&self) -> impl Iterator<Item = Result<TextMode>> + '_ {
+        let mut seen: Vec<(usize, usize)> = Vec::new();
+        self.modes().filter(move |m| match m {
+            Ok(m) if seen.contains(&m.size()) => false,
+            Ok(m) => {
+                seen.push(m.size());
+                true
+            }
+            Err(_) => true,
+        })
+    }
+
     /// Current [`TextMode`]
+    ///
+    /// Returns [`Status::UNSUPPORTED`] if firmware did not provide a mode
     pub fn mode(&self) -> Result<TextMode> {
-        // Safety: types
-        let mode = unsafe { (*self.interface().mode).mode } as u32;
+        let mode = self.raw_mode().ok_or(Status::UNSUPPORTED)?.mode as u32;
         let info = self.query_mode(mode)?;
         Ok(TextMode::new(mode, info.size()))
     }
 
+    /// Size of the current [`TextMode`], as `(cols, rows)`
+    ///
+    /// Firmware is only queried once per mode, the result is cached until
+    /// the mode changes, or [`SimpleTextOutput::set_mode`] or
+    /// [`SimpleTextOutput::reset`] are called.
+    ///
+    /// It is used to validate cursor positions, and for scrolling.
+    pub fn current_size(&self) -> Result<(usize, usize)> {
+        let mode = self.raw_mode().ok_or(Status::UNSUPPORTED)?.mode;
+        if let Some(size) = SIZE_CACHE.get(self.interface, mode) {
+            return Ok(size);
+        }
+        let size = self.query_mode(mode as u32)?.size();
+        SIZE_CACHE.set(self.interface, mode, size);
+        Ok(size)
+    }
+
+    /// Current cursor position, as `(col, row)`
+    ///
+    /// If firmware did not provide a mode, this is `(0, 0)`
+    pub fn cursor_position(&self) -> (usize, usize) {
+        self.raw_mode().map_or((0, 0), |mode| {
+            (mode.cursor_column as usize, mode.cursor_row as usize)
+        })
+    }
+
+    /// Set the cursor position to `(col, row)`
+    ///
+    /// Returns [`Status::UNSUPPORTED`] if the position is outside of
+    /// [`SimpleTextOutput::current_size`]
+    pub fn set_cursor_position(&self, col: usize, row: usize) -> Result<()> {
+        let (cols, rows) = self.current_size()?;
+        if col >= cols || row >= rows {
+            return Err(Status::UNSUPPORTED.into());
+        }
+        let set = self
+            .interface()
+            .set_cursor_position
+            .ok_or(Status::UNSUPPORTED)?;
+        // Safety: Construction ensures these are valid
+        unsafe { (set)(self.interface, col, row) }.into()
+    }
+
+    /// Scroll the screen up by `lines`, leaving the cursor at the start of
+    /// the bottom row
+    ///
+    /// See [`SimpleTextOutput::current_size`]
+    pub fn scroll_up(&self, lines: usize) -> Result<()> {
+        let (_, rows) = self.current_size()?;
+        self.set_cursor_position(0, rows.saturating_sub(1))?;
+        for _ in 0..lines {
+            self.output_string("\r\n")?;
+        }
+        Ok(())
+    }
+
     fn max_mode(&self) -> i32 {
-        // Safety: Type system
-        unsafe { (*self.interface().mode).max_mode }
+        self.raw_mode().map_or(0, |mode| mode.max_mode)
+    }
+
+    /// The raw mode, or [`None`] if firmware did not provide one
+    fn raw_mode(&self) -> Option<&RawTextMode> {
+        let mode = self.interface().mode;
+        if mode.is_null() {
+            return None;
+        }
+        // Safety: Firmware ensures `mode` is valid, checked for null above
+        Some(unsafe { &*mode })
     }
 }
 
@@ -242,16 +400,23 @@ impl<'table> SimpleTextOutput<'table> {
         let nul = s.split_once('\0');
         let s = if let Some((s, _)) = nul { s } else { s };
 
-        let ret = match self.output_string(s) {
+        let out = |s: &str| match self.output_string(s) {
             Ok(()) => Ok(()),
             Err(e) if e.status() == Status::WARN_UNKNOWN_GLYPH => Ok(()),
             Err(_) => Err(fmt::Error),
         };
-        let ret = if s.ends_with('\n') && nul.is_none() {
-            ret.and_then(|_| self.output_string("\r").map_err(|_| fmt::Error))
-        } else {
-            ret
-        };
+
+        // Every newline becomes `\r\n`, including any existing `\r\n`
+        let mut lines = s.split('\n').peekable();
+        let mut ret = Ok(());
+        while let Some(line) = lines.next() {
+            if lines.peek().is_some() {
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                ret = ret.and_then(|_| out(line)).and_then(|_| out("\r\n"));
+            } else if !line.is_empty() {
+                ret = ret.and_then(|_| out(line));
+            }
+        }
 
         if nul.is_none() {
             ret
@@ -266,8 +431,7 @@ impl<'table> SimpleTextOutput<'table> {
 
 /// All failures are treated as [`Status::DEVICE_ERROR`].
 ///
-/// Warnings are ignored. Ending Newlines are turned into \n\r.
-/// Interior newlines are not.
+/// Warnings are ignored. Newlines are turned into `\r\n`.
 // #[cfg(no)]
 impl<'t> Write for SimpleTextOutput<'t> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
@@ -306,3 +470,273 @@ impl TextMode {
         self.size
     }
 }
+
+interface!(
+    /// UEFI Absolute Pointer protocol
+    ///
+    /// Provides input from pointer devices such as touchscreens,
+    /// which report absolute coordinates.
+    #[Protocol("8D59D32B-C655-4AE9-9B15-F25904992A43", crate("crate"))]
+    AbsolutePointer(RawAbsolutePointer)
+);
+
+impl<'table> AbsolutePointer<'table> {
+    /// Reset the pointer device
+    ///
+    /// If `extended` is true, the device may perform a more exhaustive
+    /// verification.
+    pub fn reset(&self, extended: bool) -> Result<()> {
+        let reset = self.interface().reset.ok_or(Status::UNSUPPORTED)?;
+        // Safety: Construction ensures these are valid
+        unsafe { (reset)(self.interface, extended.into()) }.into()
+    }
+
+    /// Get the current [`PointerState`], or [`None`] if it has not changed
+    /// since the last call.
+    pub fn get_state(&self) -> Result<Option<PointerState>> {
+        let get = self.interface().get_state.ok_or(Status::UNSUPPORTED)?;
+        let mut state = RawAbsolutePointerState::default();
+        // Safety: Construction ensures these are valid
+        let ret = unsafe { (get)(self.interface, &mut state) };
+        if ret.is_success() {
+            Ok(Some(PointerState { state }))
+        } else if ret == Status::NOT_READY {
+            Ok(None)
+        } else {
+            Err(ret.into())
+        }
+    }
+
+    /// [`Event`] signaled when input is available from [`AbsolutePointer::get_state`]
+    ///
+    /// See [`crate::table::BootServices::check_event`]
+    pub fn wait_for_input(&self) -> Event {
+        self.interface().wait_for_input
+    }
+
+    /// The [`PointerMode`] of this device, or [`None`]
+    pub fn mode(&self) -> Option<PointerMode> {
+        let mode = self.interface().mode;
+        if mode.is_null() {
+            return None;
+        }
+        // Safety: Firmware ensures `mode` is valid, checked for null above
+        Some(PointerMode {
+            mode: unsafe { *mode },
+        })
+    }
+}
+
+/// Current state of an [`AbsolutePointer`]
+#[derive(Debug, Clone, Copy)]
+pub struct PointerState {
+    state: RawAbsolutePointerState,
+}
+
+impl PointerState {
+    /// Touch is active
+    pub const TOUCH_ACTIVE: u32 = 0x1;
+
+    /// Alternate button is active
+    pub const ALT_ACTIVE: u32 = 0x2;
+
+    /// Current position, as `(x, y, z)`
+    ///
+    /// These are within the range of [`PointerMode::min`] and
+    /// [`PointerMode::max`]
+    pub fn position(&self) -> (u64, u64, u64) {
+        (self.state.x, self.state.y, self.state.z)
+    }
+
+    /// Currently active button bits
+    ///
+    /// See [`PointerState::TOUCH_ACTIVE`] and [`PointerState::ALT_ACTIVE`]
+    pub fn buttons(&self) -> u32 {
+        self.state.active_buttons
+    }
+}
+
+/// Mode information for an [`AbsolutePointer`]
+#[derive(Debug, Clone, Copy)]
+pub struct PointerMode {
+    mode: RawAbsolutePointerMode,
+}
+
+impl PointerMode {
+    /// Minimum position, as `(x, y, z)`
+    pub fn min(&self) -> (u64, u64, u64) {
+        (self.mode.min_x, self.mode.min_y, self.mode.min_z)
+    }
+
+    /// Maximum position, as `(x, y, z)`
+    ///
+    /// An axis with a maximum of `0` is not supported
+    pub fn max(&self) -> (u64, u64, u64) {
+        (self.mode.max_x, self.mode.max_y, self.mode.max_z)
+    }
+
+    /// Whether the device supports an alternate button
+    pub fn supports_alt(&self) -> bool {
+        self.mode.attributes & 0x1 != 0
+    }
+
+    /// Whether the device reports pressure on the `z` axis
+    pub fn supports_pressure(&self) -> bool {
+        self.mode.attributes & 0x2 != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec::Vec};
+    use core::ptr::null_mut;
+
+    use super::*;
+    use crate::nuefi_core::base::{decode_ucs2, Char16};
+
+    /// A [`RawSimpleTextOutput`] that captures everything written to it
+    #[repr(C)]
+    struct Capture {
+        raw: RawSimpleTextOutput,
+        out: Vec<Char16>,
+    }
+
+    impl Capture {
+        fn new() -> Self {
+            unsafe extern "efiapi" fn output_string(
+                this: *mut RawSimpleTextOutput,
+                string: *const Char16,
+            ) -> Status {
+                // Safety: `this` is always the first field of a `Capture`
+                let this = unsafe { &mut *(this as *mut Capture) };
+                let mut i = 0;
+                // Safety: `string` is nul terminated
+                unsafe {
+                    while *string.add(i) != 0 {
+                        this.out.push(*string.add(i));
+                        i += 1;
+                    }
+                }
+                Status::SUCCESS
+            }
+
+            Self {
+                raw: RawSimpleTextOutput {
+                    reset: None,
+                    output_string: Some(output_string),
+                    test_string: None,
+                    query_mode: None,
+                    set_mode: None,
+                    set_attribute: None,
+                    clear_screen: None,
+                    set_cursor_position: None,
+                    enable_cursor: None,
+                    mode: null_mut(),
+                },
+                out: Vec::new(),
+            }
+        }
+
+        /// Run `f` with a [`SimpleTextOutput`] for us, returning what was
+        /// written.
+        fn run(mut self, f: impl FnOnce(&SimpleTextOutput) -> fmt::Result) -> String {
+            {
+                // Safety: `self` is valid and outlives `out`
+                let out = unsafe { SimpleTextOutput::new(&mut self.raw) };
+                f(&out).unwrap();
+            }
+            decode_ucs2(&self.out)
+        }
+    }
+
+    #[test]
+    fn writeln_crlf() {
+        let s = Capture::new().run(|mut out| writeln!(out, "hello"));
+        assert_eq!(s, "hello\r\n");
+    }
+
+    #[test]
+    fn interior_newlines() {
+        let s = Capture::new().run(|mut out| write!(out, "a\nb\n"));
+        assert_eq!(s, "a\r\nb\r\n");
+
+        let s = Capture::new().run(|mut out| write!(out, "a\r\nb"));
+        assert_eq!(s, "a\r\nb");
+    }
+
+    #[test]
+    fn no_trailing_newline() {
+        let s = Capture::new().run(|mut out| write!(out, "a"));
+        assert_eq!(s, "a");
+    }
+
+    #[test]
+    fn null_mode() {
+        Capture::new().run(|out| {
+            let (f, b) = out.attributes();
+            assert_eq!(
+                (f.0, b.0),
+                (TextForeground::WHITE.0, TextBackground::BLACK.0)
+            );
+            assert_eq!(out.cursor_position(), (0, 0));
+            assert!(out.mode().is_err());
+            assert_eq!(out.modes().count(), 0);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn current_size_cached() {
+        /// Number of [`query_mode`] calls
+        static QUERIES: AtomicUsize = AtomicUsize::new(0);
+
+        /// Mode `n` is `80x(25 + n)`
+        unsafe extern "efiapi" fn query_mode(
+            _this: *mut RawSimpleTextOutput,
+            mode: usize,
+            cols: *mut usize,
+            rows: *mut usize,
+        ) -> Status {
+            QUERIES.fetch_add(1, Ordering::Relaxed);
+            cols.write(80);
+            rows.write(25 + mode);
+            Status::SUCCESS
+        }
+
+        unsafe extern "efiapi" fn set_mode(
+            _this: *mut RawSimpleTextOutput,
+            _mode: usize,
+        ) -> Status {
+            Status::SUCCESS
+        }
+
+        let mut mode = RawTextMode {
+            max_mode: 2,
+            mode: 0,
+            attribute: 0,
+            cursor_column: 0,
+            cursor_row: 0,
+            cursor_visible: false,
+        };
+        let mut cap = Capture::new();
+        cap.raw.query_mode = Some(query_mode);
+        cap.raw.set_mode = Some(set_mode);
+        cap.raw.mode = &mut mode;
+        cap.run(|out| {
+            assert_eq!(out.current_size().unwrap(), (80, 25));
+            assert_eq!(out.current_size().unwrap(), (80, 25));
+            assert_eq!(QUERIES.load(Ordering::Relaxed), 1);
+
+            out.set_mode(0).unwrap();
+            assert_eq!(out.current_size().unwrap(), (80, 25));
+            assert_eq!(QUERIES.load(Ordering::Relaxed), 2);
+
+            // Mode changed behind our back
+            // Safety: `mode` outlives `out`, and nothing else references it
+            unsafe { (*out.interface().mode).mode = 1 };
+            assert_eq!(out.current_size().unwrap(), (80, 26));
+            assert_eq!(QUERIES.load(Ordering::Relaxed), 3);
+            Ok(())
+        });
+    }
+}
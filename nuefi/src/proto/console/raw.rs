@@ -2,7 +2,7 @@
 
 use core::{fmt, ptr::null_mut};
 
-use crate::nuefi_core::base::{Char16, Status};
+use crate::nuefi_core::base::{Boolean, Char16, Event, Status};
 
 #[derive(Debug)]
 #[repr(C)]
@@ -54,3 +54,38 @@ pub struct RawSimpleTextOutput {
     pub enable_cursor: Option<unsafe extern "efiapi" fn(this: *mut Self, visible: bool) -> Status>,
     pub mode: *mut RawTextMode,
 }
+
+/// `EFI_ABSOLUTE_POINTER_MODE`
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RawAbsolutePointerMode {
+    pub min_x: u64,
+    pub min_y: u64,
+    pub min_z: u64,
+    pub max_x: u64,
+    pub max_y: u64,
+    pub max_z: u64,
+    pub attributes: u32,
+}
+
+/// `EFI_ABSOLUTE_POINTER_STATE`
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C)]
+pub struct RawAbsolutePointerState {
+    pub x: u64,
+    pub y: u64,
+    pub z: u64,
+    pub active_buttons: u32,
+}
+
+#[repr(C)]
+pub struct RawAbsolutePointer {
+    pub reset: Option<unsafe extern "efiapi" fn(this: *mut Self, extended: Boolean) -> Status>,
+
+    pub get_state: Option<
+        unsafe extern "efiapi" fn(this: *mut Self, state: *mut RawAbsolutePointerState) -> Status,
+    >,
+
+    pub wait_for_input: Event,
+    pub mode: *mut RawAbsolutePointerMode,
+}
@@ -12,7 +12,9 @@ use alloc::{
 };
 use core::{
     ffi::c_void,
+    iter::from_fn,
     mem::{size_of, transmute},
+    ptr::null_mut,
     slice::from_raw_parts,
 };
 
@@ -31,7 +33,11 @@ use crate::{
     error::{Result, Status},
     get_boot_table,
     mem::MemoryType,
-    string::UefiString,
+    nuefi_core::{
+        base::encode_ucs2,
+        proto::device_path::{DevicePathSubType, DevicePathType},
+    },
+    string::{PathBuf, UefiString},
     table::BootServices,
     util::interface,
     Protocol,
@@ -39,40 +45,36 @@ use crate::{
 
 /// Helper to get [`DevicePathUtil`]
 fn get_dev_util<'proto>(
+    boot: &BootServices<'_>,
     _t: &'proto DevicePath<'_>,
 ) -> Result<Scope<'proto, DevicePathUtil<'proto>>> {
-    if let Some(table) = get_boot_table() {
-        let boot = table.boot();
-        let util = boot.get_protocol::<DevicePathUtil>()?;
-
-        // Safety: This is required because our local table is an implementation
-        // detail.
-        //
-        // The correct lifetime is `'proto`,
-        // referencing the DevicePath calling us.
-        unsafe { Ok(transmute(util)) }
-    } else {
-        Err(Status::UNSUPPORTED.into())
-    }
+    let util = boot
+        .get_protocol::<DevicePathUtil>()?
+        .ok_or(Status::UNSUPPORTED)?;
+
+    // Safety: This is required because our local table is an implementation
+    // detail.
+    //
+    // The correct lifetime is `'proto`,
+    // referencing the DevicePath calling us.
+    unsafe { Ok(transmute(util)) }
 }
 
 /// Helper to get [`DevicePathToText`]
 fn get_dev_text<'proto>(
+    boot: &BootServices<'_>,
     _t: &'proto DevicePath<'_>,
 ) -> Result<Scope<'proto, DevicePathToText<'proto>>> {
-    if let Some(table) = get_boot_table() {
-        let boot = table.boot();
-        let util = boot.get_protocol::<DevicePathToText>()?;
-
-        // Safety: This is required because our local table is an implementation
-        // detail.
-        //
-        // The correct lifetime is `'proto`,
-        // referencing the DevicePath calling us.
-        unsafe { Ok(transmute(util)) }
-    } else {
-        Err(Status::UNSUPPORTED.into())
-    }
+    let util = boot
+        .get_protocol::<DevicePathToText>()?
+        .ok_or(Status::UNSUPPORTED)?;
+
+    // Safety: This is required because our local table is an implementation
+    // detail.
+    //
+    // The correct lifetime is `'proto`,
+    // referencing the DevicePath calling us.
+    unsafe { Ok(transmute(util)) }
 }
 
 interface!(
@@ -81,73 +83,190 @@ interface!(
 );
 
 impl<'table> DevicePath<'table> {
+    /// Validate that `bytes` contains a well formed device path,
+    /// such as from a UEFI variable, and borrow it as a [`DevicePath`]
+    ///
+    /// See [`RawDevicePath::validate`] for details.
+    pub fn validate(bytes: &'table [u8]) -> Result<DevicePath<'table>> {
+        let hdr = RawDevicePath::validate(bytes)?;
+        // Safety: `hdr` was validated above, and lives for `'table`.
+        // Device paths are never written through.
+        Ok(unsafe { DevicePath::new(hdr as *const RawDevicePath as *mut _) })
+    }
+
     /// Free the DevicePath
     pub(crate) fn free(&mut self, boot: &BootServices) -> Result<()> {
         // Safety: Construction ensures these are valid
         unsafe { boot.free_pool(self.interface as *mut c_void) }
     }
 
+    /// Number of nodes in this path, NOT including the End Of Path node.
+    ///
+    /// End Of Instance nodes are counted.
+    pub fn node_count(&self) -> usize {
+        self.nodes().count()
+    }
+
+    /// Iterator over pointers to every node in this path,
+    /// NOT including the End Of Path node.
+    ///
+    /// Each node is valid for at least its header length.
+    pub(crate) fn nodes(&self) -> impl Iterator<Item = *const RawDevicePath> + '_ {
+        let mut node = self.interface as *const u8;
+        from_fn(move || {
+            // Safety: Construction ensures this is a valid path, and
+            // every node in it is valid for at least a header.
+            // The header is packed, so alignment doesn't matter.
+            let hdr = unsafe { &*(node as *const RawDevicePath) };
+            if hdr.ty == DevicePathType::END && hdr.sub_ty == DevicePathSubType::END_ENTIRE {
+                return None;
+            }
+            let len = u16::from_le_bytes(hdr.len) as usize;
+            // Malformed path, don't loop forever
+            if len < size_of::<RawDevicePath>() {
+                return None;
+            }
+            let cur = node as *const RawDevicePath;
+            // Safety: `len` is the size of this node, so the next one follows
+            node = unsafe { node.add(len) };
+            Some(cur)
+        })
+    }
+
+    /// Size of this path in bytes, including the End Of Path node.
+    pub fn size(&self) -> usize {
+        let nodes: usize = self
+            .nodes()
+            // Safety: `nodes` only yields nodes valid for at least a header
+            .map(|node| unsafe { u16::from_le_bytes((*node).len) as usize })
+            .sum();
+        // The End node is always just a header.
+        // A malformed path ends here too.
+        nodes + size_of::<RawDevicePath>()
+    }
+
+    /// The raw bytes of this path, including the End Of Path node.
+    ///
+    /// See [`DevicePath::size`]
+    pub fn as_bytes(&self) -> &[u8] {
+        // Safety: Construction ensures this is a valid path,
+        // and `size` only covers nodes within it.
+        unsafe { from_raw_parts(self.interface as *const u8, self.size()) }
+    }
+
     /// Duplicate/clone the path
     ///
+    /// This uses the global boot table, see [`DevicePath::duplicate_in`]
+    /// to pass it explicitly.
+    ///
     /// See [`DevicePathUtil::duplicate`]
     // FIXME: These leak memory.
+    #[deprecated(note = "use `DevicePath::duplicate_in`")]
     pub fn duplicate(&self) -> Result<DevicePath<'table>> {
-        if let Some(table) = get_boot_table() {
-            let boot = table.boot();
-            // TODO: Implement DevicePath ourselves in pure Rust and just do it ourselves?
-            let util = get_dev_util(self)?;
-            let s = util.duplicate(self)?;
-            // Safety: This is required because our local table is an implementation detail
-            // The correct lifetime is `'table`
-            unsafe { Ok(transmute(s)) }
-        } else {
-            Err(Status::DEVICE_ERROR.into())
-        }
+        let table = get_boot_table().ok_or(Status::DEVICE_ERROR)?;
+        self.duplicate_in(&table.boot())
+    }
+
+    /// Duplicate/clone the path, using `boot`
+    ///
+    /// See [`DevicePathUtil::duplicate`]
+    // FIXME: These leak memory.
+    pub fn duplicate_in(&self, boot: &BootServices) -> Result<DevicePath<'table>> {
+        // TODO: Implement DevicePath ourselves in pure Rust and just do it ourselves?
+        let util = get_dev_util(boot, self)?;
+        let s = util.duplicate(self)?;
+        // Safety: This is required because our local table is an implementation detail
+        // The correct lifetime is `'table`
+        unsafe { Ok(transmute(s)) }
     }
 
     /// Get this DevicePath as a [`UefiString`] using [`DevicePathToText`]
-    pub fn to_uefi_string(&self) -> Result<UefiString> {
-        if let Some(table) = get_boot_table() {
-            let boot = table.boot();
-            // TODO: Implement DevicePath ourselves in pure Rust and just do it ourselves?
-            let util = get_dev_text(self)?;
-            let s = util.convert_device_path_to_text(self)?;
-            Ok(s)
-        } else {
-            Err(Status::DEVICE_ERROR.into())
-        }
+    ///
+    /// The returned [`UefiString`] owns its own buffer, freed on [`Drop`],
+    /// and is independent of the borrow of `self`.
+    ///
+    /// This uses the global boot table, libraries managing their own table
+    /// should prefer [`DevicePath::to_uefi_string_in`].
+    #[deprecated(note = "use `DevicePath::to_uefi_string_in`")]
+    pub fn to_uefi_string(&self) -> Result<UefiString<'table>> {
+        let table = get_boot_table().ok_or(Status::DEVICE_ERROR)?;
+        self.to_uefi_string_in(&table.boot())
+    }
+
+    /// Get this DevicePath as a [`UefiString`] using [`DevicePathToText`]
+    /// from `boot`
+    ///
+    /// See [`DevicePath::to_uefi_string`]
+    pub fn to_uefi_string_in(&self, boot: &BootServices) -> Result<UefiString<'table>> {
+        // TODO: Implement DevicePath ourselves in pure Rust and just do it ourselves?
+        let util = get_dev_text(boot, self)?;
+        let s = util.convert_device_path_to_text(self)?;
+        // Safety: This is required because our local table is an implementation detail
+        // The string is a new allocation owned by us, so the correct lifetime
+        // is `'table`, not the borrow of `self`
+        unsafe { Ok(transmute(s)) }
     }
 
     /// Get this DevicePath as a [`String`] using [`DevicePathToText`]
+    ///
+    /// This uses the global boot table, see [`DevicePath::to_string_in`]
+    /// to pass it explicitly.
+    #[deprecated(note = "use `DevicePath::to_string_in`")]
     pub fn to_string(&self) -> Result<String> {
-        Ok(self.to_uefi_string()?.to_string())
+        let table = get_boot_table().ok_or(Status::DEVICE_ERROR)?;
+        self.to_string_in(&table.boot())
+    }
+
+    /// Get this DevicePath as a [`String`] using [`DevicePathToText`]
+    /// from `boot`
+    pub fn to_string_in(&self, boot: &BootServices) -> Result<String> {
+        Ok(self.to_uefi_string_in(boot)?.to_string())
     }
 
     /// Append `node` to ourselves, returning a new path.
+    ///
+    /// This uses the global boot table, see [`DevicePath::append_in`]
+    /// to pass it explicitly.
     // FIXME: These leak memory.
+    #[deprecated(note = "use `DevicePath::append_in`")]
     pub fn append(&self, node: &DevicePath) -> Result<DevicePath<'table>> {
-        if let Some(table) = get_boot_table() {
-            let boot = table.boot();
-            // TODO: Implement DevicePath ourselves in pure Rust and just do it ourselves?
-            let util = get_dev_util(self)?;
-            let s = util.append(self, node);
-            // Safety: This is required because our local table is an implementation detail
-            // The correct lifetime is `'table`
-            unsafe { Ok(transmute(s)) }
-        } else {
-            Err(Status::DEVICE_ERROR.into())
-        }
+        let table = get_boot_table().ok_or(Status::DEVICE_ERROR)?;
+        self.append_in(&table.boot(), node)
+    }
+
+    /// Append `node` to ourselves using `boot`, returning a new path.
+    // FIXME: These leak memory.
+    pub fn append_in(&self, boot: &BootServices, node: &DevicePath) -> Result<DevicePath<'table>> {
+        // TODO: Implement DevicePath ourselves in pure Rust and just do it ourselves?
+        let util = get_dev_util(boot, self)?;
+        let s = util.append(self, node);
+        // Safety: This is required because our local table is an implementation detail
+        // The correct lifetime is `'table`
+        unsafe { Ok(transmute(s)) }
     }
 
     /// Append the UEFI file path, returning the new device path
+    ///
+    /// This uses the global boot table, see
+    /// [`DevicePath::append_file_path_in`] to pass it explicitly.
     // FIXME: These leak memory.
+    #[deprecated(note = "use `DevicePath::append_file_path_in`")]
     pub fn append_file_path(&self, path: &str) -> Result<DevicePath<'table>> {
         let table = get_boot_table().ok_or(Status::UNSUPPORTED)?;
-        let boot = table.boot();
+        self.append_file_path_in(&table.boot(), path)
+    }
+
+    /// Append the UEFI file path using `boot`, returning the new device path
+    // FIXME: These leak memory.
+    pub fn append_file_path_in(
+        &self,
+        boot: &BootServices,
+        path: &str,
+    ) -> Result<DevicePath<'table>> {
         // log::trace!("Path: {path}");
 
         let hdr_size = size_of::<RawDevicePath>();
-        let path: Vec<u16> = path.encode_utf16().chain([0]).collect();
+        let path = encode_ucs2(path);
         let path_len = path.len() * 2;
 
         let cap = path_len + hdr_size + hdr_size;
@@ -183,7 +302,7 @@ impl<'table> DevicePath<'table> {
             // log::trace!("Node: {:#?}", node.to_string());
 
             // Append it
-            let ret = self.append(&node)?;
+            let ret = self.append_in(boot, &node)?;
 
             // Free our data
             boot.free_pool(data.as_ptr().cast())?;
@@ -224,6 +343,75 @@ impl<'table> DevicePathUtil<'table> {
         }
     }
 
+    /// Create a new path containing a single node of type `ty` and sub type
+    /// `sub_ty`, followed by `data`
+    ///
+    /// The End Of Path node is added automatically.
+    ///
+    /// This uses the global boot table, see [`DevicePathUtil::create_node_in`]
+    /// to pass it explicitly.
+    ///
+    /// Returns [`Status::INVALID_PARAMETER`] if `data` is too large
+    /// for a node.
+    pub fn create_node(&self, ty: u8, sub_ty: u8, data: &[u8]) -> Result<PathBuf<'table>> {
+        let table = get_boot_table().ok_or(Status::DEVICE_ERROR)?;
+        self.create_node_in(&table.boot(), ty, sub_ty, data)
+    }
+
+    /// Create a new path containing a single node, using `boot`
+    ///
+    /// See [`DevicePathUtil::create_node`]
+    pub fn create_node_in(
+        &self,
+        boot: &BootServices,
+        ty: u8,
+        sub_ty: u8,
+        data: &[u8],
+    ) -> Result<PathBuf<'table>> {
+        let cdn = self
+            .interface()
+            .create_device_node
+            .ok_or(Status::UNSUPPORTED)?;
+        let adn = self
+            .interface()
+            .append_device_node
+            .ok_or(Status::UNSUPPORTED)?;
+
+        let hdr_size = size_of::<RawDevicePath>();
+        let len = (hdr_size + data.len())
+            .try_into()
+            .map_err(|_| Status::INVALID_PARAMETER)?;
+
+        // Safety: Construction ensures these are valid
+        let node = unsafe { (cdn)(ty, sub_ty, len) };
+        if node.is_null() {
+            return Err(Status::OUT_OF_RESOURCES.into());
+        }
+
+        // Safety:
+        // - `node` is valid for `len` bytes, and we only write after the
+        //   header.
+        // - Appending to a null path copies `node` and adds an End node.
+        let path = unsafe {
+            let ptr = node.cast::<u8>().add(hdr_size);
+            ptr.copy_from_nonoverlapping(data.as_ptr(), data.len());
+            (adn)(null_mut(), node)
+        };
+        // Safety: `node` was allocated by firmware from pool,
+        // and is not used after being freed
+        let freed = unsafe { boot.free_pool(node.cast()) };
+        if path.is_null() {
+            return Err(Status::OUT_OF_RESOURCES.into());
+        }
+        if let Err(e) = freed {
+            // Safety: `path` is a new path from pool, owned by us
+            let _ = unsafe { boot.free_pool(path.cast()) };
+            return Err(e);
+        }
+        // Safety: `path` is non-null, and a new path owned by us
+        unsafe { Ok(PathBuf::new(DevicePath::from_raw(path))) }
+    }
+
     /// Append the specified [`DevicePath`] *node*
     pub fn append(&self, path: &DevicePath, node: &DevicePath) -> DevicePath<'table> {
         // Safety: Construction ensures these are valid
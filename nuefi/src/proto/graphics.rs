@@ -16,6 +16,7 @@ use self::raw::RawGraphicsMode;
 use crate::{
     error::{Result, Status},
     get_boot_table,
+    mem::PhysicalAddress,
     util::interface,
     Protocol,
 };
@@ -29,10 +30,27 @@ interface!(
 
 impl<'table> GraphicsOutput<'table> {
     /// Set the graphic mode to number `mode`
+    ///
+    /// Returns [`Status::INVALID_PARAMETER`] if `mode` is not less than
+    /// [`GraphicsOutput::mode_count`].
+    ///
+    /// If firmware fails to set the mode, a best-effort attempt is made to
+    /// restore the previous mode, and the original error is returned.
     // FIXME: This needs to invalidate the `Framebuffer`
     pub fn set_mode(&self, mode: u32) -> Result<()> {
+        if mode >= self.mode_count() {
+            return Err(Status::INVALID_PARAMETER.into());
+        }
+        let sm = self.interface().set_mode.ok_or(Status::UNSUPPORTED)?;
+        let prev = self.mode_raw().mode;
+
         // Safety: Construction ensures these are valid
-        unsafe { (self.interface().set_mode.unwrap())(self.interface, mode) }.into()
+        let ret = unsafe { (sm)(self.interface, mode) };
+        if ret.is_error() && self.mode_raw().mode != prev {
+            // Safety: Construction ensures these are valid
+            let _ = unsafe { (sm)(self.interface, prev) };
+        }
+        ret.into()
     }
 
     pub fn query_mode(&self, mode: u32) -> Result<GraphicsMode> {
@@ -66,7 +84,7 @@ impl<'table> GraphicsOutput<'table> {
     pub fn modes(&self) -> impl Iterator<Item = Result<GraphicsMode>> + '_ {
         let mut mode = 0;
         core::iter::from_fn(move || {
-            if mode >= self.max_mode() {
+            if mode >= self.mode_count() {
                 return None;
             }
             let m = self.query_mode(mode);
@@ -75,6 +93,17 @@ impl<'table> GraphicsOutput<'table> {
         })
     }
 
+    /// Every supported resolution, without duplicates,
+    /// sorted from most to fewest pixels.
+    ///
+    /// Modes that fail to be queried are skipped.
+    pub fn sorted_resolutions(&self) -> Vec<(u32, u32)> {
+        let mut res: Vec<(u32, u32)> = self.modes().filter_map(|m| Some(m.ok()?.res())).collect();
+        res.sort_unstable_by_key(|&(w, h)| core::cmp::Reverse((w as u64 * h as u64, w, h)));
+        res.dedup();
+        res
+    }
+
     /// Current [`GraphicsMode`]
     pub fn mode(&self) -> GraphicsMode {
         let mode = self.mode_raw();
@@ -96,6 +125,8 @@ impl<'table> GraphicsOutput<'table> {
     ///
     /// `buffer` must be at least `width * height`
     /// or else `INVALID_PARAMETER` will be returned.
+    /// For [`BltOperation::VideoFill`] only the first pixel is needed,
+    /// and [`BltOperation::VideoToVideo`] does not use `buffer`.
     ///
     /// If the width in `buffer` is not the same as the display then
     /// `delta` must contain the data width (pixels) or else output will be
@@ -111,7 +142,12 @@ impl<'table> GraphicsOutput<'table> {
         res: (usize, usize),
         delta: usize,
     ) -> Result<()> {
-        if buffer.len() < (res.0 * res.1) {
+        let needed = match op {
+            BltOperation::VideoFill => 1,
+            BltOperation::VideoToVideo => 0,
+            _ => res.0 * res.1,
+        };
+        if buffer.len() < needed {
             return Err(Status::INVALID_PARAMETER.into());
         }
         // Safety: Construction ensures these are valid
@@ -132,6 +168,36 @@ impl<'table> GraphicsOutput<'table> {
         .into()
     }
 
+    /// Scroll `region` up by `pixels`, filling the exposed strip at the
+    /// bottom with `fill`
+    ///
+    /// If `pixels` is larger than the region, the whole region is filled.
+    pub fn scroll_up(&self, region: Rect, pixels: usize, fill: Pixel) -> Result<()> {
+        let pixels = pixels.min(region.height);
+        let kept = region.height - pixels;
+        if kept > 0 {
+            self.blt(
+                &[],
+                BltOperation::VideoToVideo,
+                (region.x, region.y + pixels),
+                (region.x, region.y),
+                (region.width, kept),
+                0,
+            )?;
+        }
+        if pixels > 0 {
+            self.blt(
+                &[fill],
+                BltOperation::VideoFill,
+                (0, 0),
+                (region.x, region.y + kept),
+                (region.width, pixels),
+                0,
+            )?;
+        }
+        Ok(())
+    }
+
     /// Get a mutable byte slice to the current framebuffer
     ///
     /// Note that each pixel `(x, y)`
@@ -143,11 +209,43 @@ impl<'table> GraphicsOutput<'table> {
             let mode = self.mode_raw();
             let ptr = mode.fb_base as *mut u8;
             let size = mode.fb_size;
-            let fb = Framebuffer::new(ptr, size, self.mode().stride());
+            let mode = self.mode();
+            let fb = Framebuffer::new(ptr, size, mode.stride(), mode.res());
             Ok(fb)
         }
     }
 
+    /// Location and layout of the current framebuffer, as plain data
+    ///
+    /// Unlike [`GraphicsOutput::framebuffer`], this does not create any
+    /// references to the framebuffer memory,
+    /// making it suitable to hand off to a kernel.
+    ///
+    /// Returns [`Status::UNSUPPORTED`] if the current mode is
+    /// [`PixelFormat::BltOnly`], and has no framebuffer.
+    pub fn framebuffer_info(&self) -> Result<FramebufferInfo> {
+        let raw = self.mode_raw();
+        let mode = self.mode();
+        let format = mode.format();
+        if format == PixelFormat::BltOnly {
+            return Err(Status::UNSUPPORTED.into());
+        }
+        Ok(FramebufferInfo {
+            base: PhysicalAddress::new(raw.fb_base),
+            size: raw.fb_size,
+            stride: mode.stride(),
+            format,
+            resolution: mode.res(),
+        })
+    }
+
+    /// Number of supported modes
+    ///
+    /// Valid mode numbers are `0..mode_count()`
+    pub fn mode_count(&self) -> u32 {
+        self.mode_raw().max_mode
+    }
+
     /// Max supported mode
     ///
     /// # Note
@@ -205,6 +303,27 @@ impl GraphicsMode {
     }
 }
 
+/// Location and layout of a framebuffer
+///
+/// See [`GraphicsOutput::framebuffer_info`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FramebufferInfo {
+    /// Physical address of the framebuffer
+    pub base: PhysicalAddress,
+
+    /// Size of the framebuffer in bytes
+    pub size: usize,
+
+    /// Pixels per scan line, which may be more than the width
+    pub stride: u32,
+
+    /// Pixel format
+    pub format: PixelFormat,
+
+    /// (width, height) in pixels
+    pub resolution: (u32, u32),
+}
+
 /// UEFI Framebuffer pixel format
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
@@ -279,9 +398,12 @@ pub struct Framebuffer<'gop> {
     /// Size of the framebuffer in bytes
     size: usize,
 
-    /// Stride of the framebuffer in bytes
+    /// Stride of the framebuffer in pixels
     stride: u32,
 
+    /// (width, height) of the framebuffer in pixels
+    res: (u32, u32),
+
     /// Holds the lifetime of our parent [`GraphicsOutput`]
     phantom: PhantomData<&'gop u8>,
 }
@@ -290,15 +412,40 @@ impl<'gop> Framebuffer<'gop> {
     /// Create new Framebuffer wrapper
     ///
     /// - `ptr` MUST be valid for `size` bytes
-    unsafe fn new(ptr: *mut u8, size: usize, stride: u32) -> Self {
+    unsafe fn new(ptr: *mut u8, size: usize, stride: u32, res: (u32, u32)) -> Self {
         Self {
             ptr,
             size,
             stride,
+            res,
             phantom: PhantomData,
         }
     }
 
+    /// Index of the [`Pixel`] at `(x, y)`,
+    /// or [`None`] if it is out of bounds
+    fn pixel_index(&self, x: u32, y: u32) -> Option<usize> {
+        let (width, height) = self.res;
+        if x >= width || y >= height {
+            return None;
+        }
+        let index = (y as usize * self.stride as usize) + x as usize;
+        if (index + 1) * size_of::<Pixel>() > self.size {
+            return None;
+        }
+        Some(index)
+    }
+
+    /// Mutable reference to the [`Pixel`] at `(x, y)`,
+    /// or [`None`] if it is outside of the current resolution
+    pub fn pixel_mut(&mut self, x: u32, y: u32) -> Option<&mut Pixel> {
+        let index = self.pixel_index(x, y)?;
+        // Safety:
+        // - `pixel_index` ensures `index` is within the framebuffer
+        // - We have unique access through `&mut self`
+        unsafe { Some(&mut *self.ptr.cast::<Pixel>().add(index)) }
+    }
+
     pub fn pixels(&self) -> &'gop [Pixel] {
         let ptr = self.ptr as *mut Pixel;
         let len = self.size / size_of::<Pixel>();
@@ -313,6 +460,31 @@ impl<'gop> Framebuffer<'gop> {
         unsafe { from_raw_parts_mut(ptr, len) }
     }
 
+    /// Iterator over each scanline of the framebuffer
+    ///
+    /// Each row is exactly the width of the current resolution,
+    /// skipping any padding from the [stride][`GraphicsMode::stride`].
+    pub fn rows(&self) -> impl Iterator<Item = &[Pixel]> + '_ {
+        let (width, height) = (self.res.0 as usize, self.res.1 as usize);
+        let stride = (self.stride as usize).max(1);
+        self.pixels()
+            .chunks(stride)
+            .take(height)
+            .map_while(move |row| row.get(..width))
+    }
+
+    /// Mutable iterator over each scanline of the framebuffer
+    ///
+    /// See [`Framebuffer::rows`]
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [Pixel]> + '_ {
+        let (width, height) = (self.res.0 as usize, self.res.1 as usize);
+        let stride = (self.stride as usize).max(1);
+        self.pixels_mut()
+            .chunks_mut(stride)
+            .take(height)
+            .map_while(move |row| row.get_mut(..width))
+    }
+
     /// Size in bytes of the framebuffer
     pub fn size(&self) -> usize {
         self.size
@@ -323,12 +495,13 @@ impl<'gop> Index<(u32, u32)> for Framebuffer<'gop> {
     type Output = Pixel;
 
     fn index(&self, (x, y): (u32, u32)) -> &Self::Output {
-        let index = ((y * self.stride) + x) as usize;
-        assert!(index <= self.size, "Framebuffer index out of bounds");
+        let index = self
+            .pixel_index(x, y)
+            .expect("Framebuffer index out of bounds");
         // Safety:
-        // - We assert `index` is within range
+        // - `pixel_index` ensures `index` is within range
         // - The type here is a `Pixel`
-        unsafe { &*self.ptr.add(index).cast::<Pixel>() }
+        unsafe { &*self.ptr.cast::<Pixel>().add(index) }
     }
 }
 
@@ -392,6 +565,34 @@ impl Coord {
     }
 }
 
+/// A rectangular region of the screen, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    /// Left edge
+    pub x: usize,
+
+    /// Top edge
+    pub y: usize,
+
+    /// Width in pixels
+    pub width: usize,
+
+    /// Height in pixels
+    pub height: usize,
+}
+
+impl Rect {
+    /// Create a new [`Rect`] at `(x, y)`, of `(width, height)`
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
 /// A double buffer for the framebuffer
 #[derive(Debug)]
 struct Double<'table> {
@@ -409,3 +610,25 @@ impl<'table> Double<'table> {
 
     // pub fn swap(&self)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_skip_stride() {
+        // 3x2 visible, with a stride of 4
+        let mut data = [Pixel::default(); 8];
+        let size = core::mem::size_of_val(&data);
+        // Safety: `data` is valid for `size` bytes
+        let mut fb = unsafe { Framebuffer::new(data.as_mut_ptr().cast(), size, 4, (3, 2)) };
+        for row in fb.rows_mut() {
+            assert_eq!(row.len(), 3);
+            row.fill(Pixel::new(1, 2, 3));
+        }
+        assert_eq!(fb.rows().count(), 2);
+        assert!(fb.rows().flatten().all(|p| p.red() == 1));
+        assert_eq!(data[3].red(), 0);
+        assert_eq!(data[7].red(), 0);
+    }
+}
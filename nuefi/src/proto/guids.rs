@@ -0,0 +1,80 @@
+//! GUIDs for every [`Protocol`][super::Protocol] nuefi wraps
+//!
+//! Useful for comparing against GUIDs returned by firmware,
+//! or when working with protocols by GUID alone.
+//!
+//! These are the same as the `GUID` associated constant on each protocol.
+use super::{
+    component_name::ComponentName2,
+    console::{AbsolutePointer, SimpleTextOutput},
+    device_path::{DevicePath, DevicePathToText, DevicePathUtil},
+    edid::{ActiveEdid, DiscoveredEdid},
+    graphics::GraphicsOutput,
+    loaded_image::LoadedImage,
+    media::{LoadFile2, SimpleFileSystem},
+    platform_init::security::{SecurityArch, SecurityArch2},
+    pxe::PxeBaseCode,
+    shell::Shell,
+    timestamp::Timestamp,
+    unicode_collation::UnicodeCollation,
+    vendor::linux::InitrdMediaGuid,
+    Protocol,
+    Guid,
+};
+
+/// [`SimpleTextOutput`]
+pub const SIMPLE_TEXT_OUTPUT: Guid = SimpleTextOutput::GUID;
+
+/// [`AbsolutePointer`]
+pub const ABSOLUTE_POINTER: Guid = AbsolutePointer::GUID;
+
+/// [`ComponentName2`]
+pub const COMPONENT_NAME2: Guid = ComponentName2::GUID;
+
+/// [`DevicePath`]
+pub const DEVICE_PATH: Guid = DevicePath::GUID;
+
+/// [`DevicePathUtil`]
+pub const DEVICE_PATH_UTIL: Guid = DevicePathUtil::GUID;
+
+/// [`DevicePathToText`]
+pub const DEVICE_PATH_TO_TEXT: Guid = DevicePathToText::GUID;
+
+/// [`ActiveEdid`]
+pub const EDID_ACTIVE: Guid = ActiveEdid::GUID;
+
+/// [`DiscoveredEdid`]
+pub const EDID_DISCOVERED: Guid = DiscoveredEdid::GUID;
+
+/// [`GraphicsOutput`]
+pub const GRAPHICS_OUTPUT: Guid = GraphicsOutput::GUID;
+
+/// [`LoadedImage`]
+pub const LOADED_IMAGE: Guid = LoadedImage::GUID;
+
+/// [`LoadFile2`]
+pub const LOAD_FILE2: Guid = LoadFile2::GUID;
+
+/// [`SimpleFileSystem`]
+pub const SIMPLE_FILE_SYSTEM: Guid = SimpleFileSystem::GUID;
+
+/// [`SecurityArch`]
+pub const SECURITY_ARCH: Guid = SecurityArch::GUID;
+
+/// [`SecurityArch2`]
+pub const SECURITY_ARCH2: Guid = SecurityArch2::GUID;
+
+/// [`PxeBaseCode`]
+pub const PXE_BASE_CODE: Guid = PxeBaseCode::GUID;
+
+/// [`Shell`]
+pub const SHELL: Guid = Shell::GUID;
+
+/// [`Timestamp`]
+pub const TIMESTAMP: Guid = Timestamp::GUID;
+
+/// [`UnicodeCollation`]
+pub const UNICODE_COLLATION2: Guid = UnicodeCollation::GUID;
+
+/// [`InitrdMediaGuid`]
+pub const LINUX_INITRD_MEDIA: Guid = InitrdMediaGuid::GUID;
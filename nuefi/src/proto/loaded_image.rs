@@ -1,10 +1,13 @@
 //! UEFI Loaded image Protocol
-use core::mem::size_of;
+use alloc::vec::Vec;
+use core::{mem::size_of, slice::from_raw_parts};
 
 use raw::RawLoadedImage;
 
 use super::{device_path::DevicePath, Guid, Protocol};
 use crate::{
+    error::{Result, Status},
+    nuefi_core::base::encode_ucs2,
     string::{Path, UefiStr},
     util::interface,
     EfiHandle,
@@ -51,6 +54,81 @@ impl<'table> LoadedImage<'table> {
         }
     }
 
+    /// Size of the LoadOptions for this loaded image, in bytes
+    ///
+    /// This is the raw `LoadOptionsSize`, and may be non-zero even if the
+    /// options pointer is null.
+    /// Prefer [`LoadedImage::options`], which accounts for that.
+    pub fn load_options_size(&self) -> u32 {
+        self.interface().options_size
+    }
+
+    /// The raw LoadOptions for this loaded image
+    ///
+    /// These are whatever our loader passed us.
+    ///
+    /// Returns [`None`] if there are no options,
+    /// meaning either the pointer is null or the size is zero.
+    /// If this is [`Some`], the options are never empty.
+    ///
+    /// See [`LoadedImage::options_str`] for Shell style options.
+    ///
+    /// # Example
+    ///
+    /// Binary options have whatever format the loader chose,
+    /// and must be bounds checked.
+    /// For example, decoding a little endian [`u32`] index:
+    ///
+    /// ```rust
+    /// # use nuefi::proto::loaded_image::LoadedImage;
+    /// fn index(image: &LoadedImage) -> Option<u32> {
+    ///     let opts = image.options()?;
+    ///     let bytes = opts.get(..4)?.try_into().ok()?;
+    ///     Some(u32::from_le_bytes(bytes))
+    /// }
+    /// ```
+    pub fn options(&self) -> Option<&[u8]> {
+        let ptr = self.interface().options;
+        let size = self.load_options_size() as usize;
+        if ptr.is_null() || size == 0 {
+            return None;
+        }
+        // Safety: Firmware/our loader ensures `options` is valid for `size`
+        // bytes
+        unsafe { Some(from_raw_parts(ptr, size)) }
+    }
+
+    /// The LoadOptions for this loaded image, interpreted as a
+    /// UTF-16 null terminated string, as the UEFI Shell passes them.
+    ///
+    /// Returns [`None`] if there are no options, see [`LoadedImage::options`].
+    ///
+    /// Options that are present but only contain a nul, an empty string,
+    /// are returned as an empty [`UefiStr`].
+    ///
+    /// Returns [`Status::INVALID_PARAMETER`] if the options are not a valid
+    /// null terminated UTF-16 buffer.
+    /// In this case they are likely binary, see [`LoadedImage::options`].
+    pub fn options_str(&self) -> Result<Option<UefiStr<'_>>> {
+        let Some(opts) = self.options() else {
+            return Ok(None);
+        };
+        // Safety: All bit patterns are valid `u16`s
+        let (pre, data, post) = unsafe { opts.align_to::<u16>() };
+        if !pre.is_empty() || !post.is_empty() {
+            return Err(Status::INVALID_PARAMETER.into());
+        }
+        let len = data
+            .iter()
+            .position(|&c| c == 0)
+            .ok_or(Status::INVALID_PARAMETER)?;
+
+        let ptr = data.as_ptr() as *mut u16;
+
+        // Safety: `ptr` is valid and contains a nul at `len`
+        unsafe { Ok(Some(UefiStr::from_ptr_len(ptr, len + 1))) }
+    }
+
     /// Set the LoadOptions for this loaded image
     ///
     /// # Panics
@@ -91,6 +169,31 @@ impl<'table> LoadedImage<'table> {
         self.set_options::<u16>(cmd.as_slice());
     }
 
+    /// Set the image load options to the command line `cmd`,
+    /// encoded as a UTF-16 null terminated string, as the UEFI Shell does.
+    ///
+    /// Returns the encoded buffer, which the options now point to.
+    ///
+    /// # Panics
+    ///
+    /// - If the encoded `cmd` is bigger than [`u32::MAX`]
+    ///
+    /// # Safety
+    ///
+    /// - You must ensure this image is, in fact,
+    ///   expecting arguments in this format.
+    /// - The returned buffer MUST live until
+    ///   [`BootServices::start_image`][start_image] is called
+    ///
+    /// [start_image]: crate::table::BootServices::start_image
+    #[must_use = "The options point into the returned buffer"]
+    pub unsafe fn set_command_line(&self, cmd: &str) -> Vec<u16> {
+        let cmd = encode_ucs2(cmd);
+        // Safety: Caller
+        self.set_options::<u16>(&cmd);
+        cmd
+    }
+
     /// Set the Device handle for this image
     ///
     /// # Safety
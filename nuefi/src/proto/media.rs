@@ -1,8 +1,8 @@
 //! UEFI Media protocols
-use alloc::{string::String, vec::Vec};
+use alloc::{string::String, vec, vec::Vec};
 use core::{
     cell::Cell,
-    iter::{from_fn, once},
+    iter::from_fn,
     marker::PhantomData,
     mem::{size_of, MaybeUninit},
     ptr::null_mut,
@@ -15,13 +15,17 @@ use raw::*;
 
 use crate::{
     error::{Result, Status},
-    proto::{Entity, Guid, Protocol},
+    nuefi_core::base::{decode_ucs2, encode_ucs2},
+    proto::{Entity, Guid, Protocol, Time},
     util::interface,
     Protocol,
 };
 
 pub mod raw;
 
+/// Size of the buffer used by [`FsHandle::copy_to`]
+const COPY_CHUNK: usize = 64 * 1024;
+
 interface!(
     #[Protocol("4006C0C1-FCB3-403E-996D-4A6C8724E06D", crate("crate"))]
     LoadFile2(RawLoadFile2)
@@ -44,15 +48,16 @@ impl<'table> SimpleFileSystem<'table> {
     /// Open the root directory of a volume
     pub fn open_volume<'h>(&self) -> Result<FsHandle<'h, 'table>> {
         let mut out = null_mut();
+        let ov = self.interface().open_volume.ok_or(Status::UNSUPPORTED)?;
         // Safety: `file` is always valid, checked for null
         // anything else is the responsibility of firmware
-        let ret = unsafe { (self.interface().open_volume.unwrap())(self.interface, &mut out) };
+        let ret = unsafe { (ov)(self.interface, &mut out) };
         if ret.is_success() {
-            assert!(
-                !out.is_null(),
-                "SimpleFileSystem returned success, but the file was null.",
-            );
-            // Safety: `FsHandle` isn't a Protocol
+            // Non-conformant firmware may return success without a file
+            if out.is_null() {
+                return Err(Status::DEVICE_ERROR.into());
+            }
+            // Safety: `FsHandle` isn't a Protocol, checked for null
             unsafe { Ok(FsHandle::new(out)) }
         } else {
             Err(ret.into())
@@ -74,6 +79,9 @@ use file_imp::FileImp;
 ///
 /// This will call [`FsHandle::close`] on [`Drop`]
 ///
+/// Methods return [`Status::UNSUPPORTED`] if the firmware did not provide
+/// the function they need.
+///
 /// See [`SimpleFileSystem`]
 // The `'this` lifetime is independent and under `'table`
 // because the `FsHandle` is independent of whatever created it,
@@ -124,19 +132,18 @@ impl<'this, 'table> FsHandle<'this, 'table> {
         flags: u64,
     ) -> Result<FsHandle<'new_this, 'table>> {
         let mut out = null_mut();
-        let name: Vec<u16> = name.encode_utf16().chain(once(0)).collect();
+        let name = encode_ucs2(name);
+        let op = self.interface().open.ok_or(Status::UNSUPPORTED)?;
 
         // Safety: `out` valid by definition, firmware
-        let ret = unsafe {
-            (self.interface().open.unwrap())(self.interface, &mut out, name.as_ptr(), mode, flags)
-        };
+        let ret = unsafe { (op)(self.interface, &mut out, name.as_ptr(), mode, flags) };
 
         if ret.is_success() {
-            assert!(
-                !out.is_null(),
-                "File returned success, but the file was null.",
-            );
-            // Safety: `FsHandle` isn't a Protocol
+            // Non-conformant firmware may return success without a file
+            if out.is_null() {
+                return Err(Status::DEVICE_ERROR.into());
+            }
+            // Safety: `FsHandle` isn't a Protocol, checked for null
             unsafe { Ok(FsHandle::new(out)) }
         } else {
             Err(ret.into())
@@ -145,7 +152,7 @@ impl<'this, 'table> FsHandle<'this, 'table> {
 
     /// Reads the buffer for [`FsHandle::read_impl`]
     fn read_impl_size(&self) -> Result<usize> {
-        let rd = self.interface().read.unwrap();
+        let rd = self.interface().read.ok_or(Status::UNSUPPORTED)?;
         let mut size = 0;
 
         // Calling to get buffer size
@@ -173,18 +180,12 @@ impl<'this, 'table> FsHandle<'this, 'table> {
     /// - `out` must be valid for `size` bytes
     unsafe fn read_impl_write(&self, size: usize, out: &mut [u8]) -> Result<usize> {
         let mut size = size;
-        let rd = self.interface().read.unwrap();
+        let rd = self.interface().read.ok_or(Status::UNSUPPORTED)?;
         let ptr = out.as_mut_ptr();
 
         // `interface`, `size`, are valid
         // `ptr` is valid for `size` bytes
-        let ret = (rd)(self.interface, &mut size, ptr);
-
-        if ret.is_success() {
-            Ok(size)
-        } else {
-            Err(ret.into())
-        }
+        (rd)(self.interface, &mut size, ptr).map_success(|| size)
     }
 
     /// Implementation of the `read` call. Returns how many bytes written
@@ -195,8 +196,6 @@ impl<'this, 'table> FsHandle<'this, 'table> {
         assert!(out.is_empty(), "Expected `out` to be empty");
         // Safety: Described within
         unsafe {
-            let rd = self.interface().read.unwrap();
-
             // Calling to get buffer size
             let mut size = self.read_impl_size()?;
 
@@ -237,6 +236,41 @@ impl<'this, 'table> FsHandle<'this, 'table> {
         self.try_exists().unwrap_or_default()
     }
 
+    /// Copy this file, from its current [`FsHandle::position`], to `dest`,
+    /// returning how many bytes were copied.
+    ///
+    /// `dest` must have been opened for writing, see [`FsHandle::create`].
+    ///
+    /// Returns [`Status::INVALID_PARAMETER`] if either handle is a directory.
+    pub fn copy_to(&self, dest: &FsHandle) -> Result<u64> {
+        if self.info()?.directory() || dest.info()?.directory() {
+            return Err(Status::INVALID_PARAMETER.into());
+        }
+        let mut buf = vec![0u8; COPY_CHUNK];
+        let mut total: u64 = 0;
+
+        loop {
+            // Safety: `buf` is valid for its length
+            let read = unsafe { self.read_impl_write(buf.len(), &mut buf)? };
+            if read == 0 {
+                break;
+            }
+
+            let mut written = 0;
+            while written < read {
+                let n = dest.write(&buf[written..read])?;
+                // Nothing written and no error, don't loop forever
+                if n == 0 {
+                    return Err(Status::DEVICE_ERROR.into());
+                }
+                written += n;
+            }
+            total += read as u64;
+        }
+
+        Ok(total)
+    }
+
     /// Read to `buf` until the end of the file,
     /// returning how many bytes were read.
     pub fn read_to_end(&self, buf: &mut Vec<u8>) -> Result<usize> {
@@ -265,9 +299,15 @@ impl<'this, 'table> FsHandle<'this, 'table> {
         self.open_impl(name, mode, flags)
     }
 
-    /// Create a new [`FsHandle`] relative to this one
+    /// Create a new [`FsHandle`] relative to this one,
+    /// opened for reading and writing.
+    ///
+    /// If it already exists, it is opened as-is.
     pub fn create<'new_this>(&self, name: &str) -> Result<FsHandle<'new_this, 'table>> {
-        todo!()
+        // Read | Write | Create
+        let mode = 0x8000000000000003;
+        let flags = 0;
+        self.open_impl(name, mode, flags)
     }
 
     /// Read the contents of the directory referred to by our handle
@@ -300,15 +340,92 @@ impl<'this, 'table> FsHandle<'this, 'table> {
         unsafe { self.read_impl_write(size, out) }
     }
 
+    /// Read exactly enough bytes to fill `buf`
+    ///
+    /// Like [`FsHandle::read`], but keeps reading until `buf` is full.
+    ///
+    /// Returns [`Status::END_OF_FILE`] if EOF was reached first,
+    /// in which case the contents of `buf` are unspecified.
+    pub fn read_exact(&self, buf: &mut [u8]) -> Result<()> {
+        let info = self.info()?;
+        if info.directory() {
+            return Err(Status::INVALID_PARAMETER.into());
+        }
+        let mut buf = buf;
+        while !buf.is_empty() {
+            // Safety: `buf` is valid for `buf.len()` bytes
+            let read = unsafe { self.read_impl_write(buf.len(), buf) }?;
+            if read == 0 {
+                return Err(Status::END_OF_FILE.into());
+            }
+            buf = &mut buf[read..];
+        }
+        Ok(())
+    }
+
+    /// Write bytes from `buf`, returning how many were actually written.
+    ///
+    /// The handle must have been opened for writing, see
+    /// [`FsHandle::create`].
+    ///
+    /// The files current [`FsHandle::position`] increases by the amount
+    /// written.
+    pub fn write(&self, buf: &[u8]) -> Result<usize> {
+        let mut size = buf.len();
+        let wr = self.interface().write.ok_or(Status::UNSUPPORTED)?;
+        // Safety: `buf` is valid for `size` bytes
+        unsafe { (wr)(self.interface, &mut size, buf.as_ptr()) }.map_success(|| size)
+    }
+
     /// Information about this [`FsHandle`]. See [`FsInfo`]
     pub fn info(&self) -> Result<FsInfo> {
+        FsInfo::from_bytes(self.info_bytes()?)
+    }
+
+    /// [`Metadata`] about this [`FsHandle`]
+    ///
+    /// This is [`FsHandle::info`] without the name.
+    pub fn metadata(&self) -> Result<Metadata> {
+        self.info().map(|i| i.metadata())
+    }
+
+    /// Set the size of the file to `size` bytes
+    ///
+    /// If `size` is smaller than the current size, the file is truncated.
+    /// If it is larger, the file is extended, and per the UEFI spec
+    /// the new space is filled with zeros.
+    ///
+    /// The file cursor is not changed.
+    pub fn set_len(&self, size: u64) -> Result<()> {
+        let guid = FsInfo::GUID;
+        let mut buf = self.info_bytes()?;
+        let si = self.interface().set_info.ok_or(Status::UNSUPPORTED)?;
+
+        // Safety: `info_bytes` ensures `buf` is at least as big as a
+        // `RawFsInfo`, and we use unaligned accesses.
+        unsafe {
+            let ptr = buf.as_mut_ptr() as *mut RawFsInfo;
+            let mut info = ptr.read_unaligned();
+            info.file_size = size;
+            ptr.write_unaligned(info);
+        }
+
+        // Safety: `buf` is a valid `FsInfo` buffer for `buf.len()` bytes,
+        // as given to us by firmware
+        unsafe { (si)(self.interface, &guid, buf.len(), buf.as_ptr()) }.into()
+    }
+
+    /// Raw bytes of the [`FsInfo`] for this handle
+    ///
+    /// This is guaranteed to be at least as big as a [`RawFsInfo`]
+    fn info_bytes(&self) -> Result<Vec<u8>> {
         let guid = FsInfo::GUID;
         let mut size: usize = 0;
         let mut out: Vec<u8> = Vec::new();
 
         // Safety: Described within
         unsafe {
-            let fp = self.interface().get_info.unwrap();
+            let fp = self.interface().get_info.ok_or(Status::UNSUPPORTED)?;
 
             // Get the buffer size
 
@@ -343,8 +460,10 @@ impl<'this, 'table> FsHandle<'this, 'table> {
                 // Out has been fully initialized, because we started initialized
                 out.set_len(size);
 
-                let info = FsInfo::from_bytes(out).unwrap();
-                Ok(info)
+                if out.len() < size_of::<RawFsInfo>() {
+                    return Err(Status::BUFFER_TOO_SMALL.into());
+                }
+                Ok(out)
             } else {
                 Err(info.into())
             }
@@ -359,37 +478,37 @@ impl<'this, 'table> FsHandle<'this, 'table> {
             return Ok(());
         }
         self.closed.set(true);
+        let cl = self.interface().close.ok_or(Status::UNSUPPORTED)?;
         // Safety: checked for null, anything else is the responsibility of firmware
         // This can only be called once.
         // Idk about real hardware yet, but
         // QEMU GP faults if this is called multiple times.
         // FIXME: QEMU/UEFI faults if `close` is called multiple times?
-        unsafe { (self.interface().close.unwrap())(self.interface) }.into()
+        unsafe { (cl)(self.interface) }.into()
     }
 
     /// Flush all data with this handle
     pub fn flush(&self) -> Result<()> {
+        let fl = self.interface().flush.ok_or(Status::UNSUPPORTED)?;
         // Safety: checked for null, anything else is the responsibility of firmware
-        unsafe { (self.interface().flush.unwrap())(self.interface) }.into()
+        unsafe { (fl)(self.interface) }.into()
     }
 
     /// Set file cursor position
+    ///
+    /// A position of [`u64::MAX`] sets the cursor to the end of the file.
     pub fn set_position(&self, pos: u64) -> Result<()> {
+        let sp = self.interface().set_pos.ok_or(Status::UNSUPPORTED)?;
         // Safety: statically valid
-        unsafe { (self.interface().set_pos.unwrap())(self.interface, pos).into() }
+        unsafe { (sp)(self.interface, pos) }.into()
     }
 
     /// Current file cursor position
     pub fn position(&self) -> Result<u64> {
         let mut pos: u64 = 0;
+        let gp = self.interface().get_pos.ok_or(Status::UNSUPPORTED)?;
         // Safety: statically valid
-        let ret = unsafe { (self.interface().get_pos.unwrap())(self.interface, &mut pos) };
-
-        if ret.is_success() {
-            Ok(pos)
-        } else {
-            Err(ret.into())
-        }
+        unsafe { (gp)(self.interface, &mut pos) }.map_success(|| pos)
     }
 }
 
@@ -413,6 +532,7 @@ pub struct FsInfo {
 }
 
 impl FsInfo {
+    const READ_ONLY: u64 = 0x1;
     const DIRECTORY: u64 = 0x10;
 
     fn new(info: RawFsInfo, name: String) -> Self {
@@ -448,13 +568,33 @@ impl FsInfo {
             let name = from_raw_parts(name.as_ptr() as *const u16, name_len);
 
             // Then decode it as UTF-16
-            let name = char::decode_utf16(name.iter().copied())
-                .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER))
-                .collect::<String>();
+            let name = decode_ucs2(name);
             Ok(FsInfo::new(info, name))
         }
     }
 
+    /// Serialize this `FsInfo` to bytes, as `set_info` expects
+    ///
+    /// This is the [`RawFsInfo`] followed by the nul terminated UCS-2 name,
+    /// with the size field set to the total length.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let name = encode_ucs2(&self.name);
+        let f_size = size_of::<RawFsInfo>();
+        let size = f_size + (name.len() * 2);
+
+        let mut info = self.info;
+        info.this_size = size as u64;
+
+        let mut out = vec![0u8; size];
+        // Safety: `out` is valid for `size` bytes, and we use
+        // unaligned writes
+        unsafe { out.as_mut_ptr().cast::<RawFsInfo>().write_unaligned(info) };
+        for (chunk, c) in out[f_size..].chunks_exact_mut(2).zip(name) {
+            chunk.copy_from_slice(&c.to_ne_bytes());
+        }
+        out
+    }
+
     /// Is this a directory or not?
     pub fn directory(&self) -> bool {
         (self.info.flags & Self::DIRECTORY) == Self::DIRECTORY
@@ -474,6 +614,80 @@ impl FsInfo {
     pub fn dev_size(&self) -> u64 {
         self.info.physical_size
     }
+
+    /// [`Metadata`] for this entity
+    pub fn metadata(&self) -> Metadata {
+        Metadata { info: self.info }
+    }
+}
+
+/// Metadata about an entity on the filesystem,
+/// similar to `std::fs::Metadata`
+///
+/// See [`FsHandle::metadata`]
+#[derive(Debug, Clone, Copy)]
+pub struct Metadata {
+    info: RawFsInfo,
+}
+
+impl Metadata {
+    /// Size of the file in bytes
+    pub fn len(&self) -> u64 {
+        self.info.file_size
+    }
+
+    /// Whether the file is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether this is a directory
+    pub fn is_dir(&self) -> bool {
+        (self.info.flags & FsInfo::DIRECTORY) == FsInfo::DIRECTORY
+    }
+
+    /// Whether this is a regular file
+    pub fn is_file(&self) -> bool {
+        !self.is_dir()
+    }
+
+    /// The [`Permissions`] of this entity
+    pub fn permissions(&self) -> Permissions {
+        Permissions {
+            readonly: (self.info.flags & FsInfo::READ_ONLY) == FsInfo::READ_ONLY,
+        }
+    }
+
+    /// When this entity was created
+    pub fn created(&self) -> Time {
+        self.info.create_time
+    }
+
+    /// When this entity was last accessed
+    pub fn accessed(&self) -> Time {
+        self.info.last_access_time
+    }
+
+    /// When this entity was last modified
+    pub fn modified(&self) -> Time {
+        self.info.modification_time
+    }
+}
+
+/// Permissions of an entity on the filesystem,
+/// similar to `std::fs::Permissions`
+///
+/// See [`Metadata::permissions`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions {
+    readonly: bool,
+}
+
+impl Permissions {
+    /// Whether the entity is read-only
+    pub fn readonly(&self) -> bool {
+        self.readonly
+    }
 }
 
 pub mod iter {
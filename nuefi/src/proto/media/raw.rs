@@ -131,7 +131,7 @@ pub struct RawFsHandle {
 }
 
 /// UEFI [`RawFsInfo`] information
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 #[repr(C)]
 pub struct RawFsInfo {
     pub this_size: u64,
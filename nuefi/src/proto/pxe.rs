@@ -0,0 +1,208 @@
+//! UEFI PXE Base Code Protocol
+use alloc::{vec, vec::Vec};
+use core::ptr::{null, null_mut};
+
+use raw::*;
+
+use crate::{
+    error::{Result, Status},
+    nuefi_core::base::{IP, IPV4},
+    proto::{Guid, Protocol},
+    util::interface,
+    Protocol,
+};
+
+pub mod raw;
+
+/// Offset of the `siaddr` field in a DHCPv4 packet
+const DHCPV4_SERVER_IP: usize = 20;
+
+interface!(
+    /// UEFI PXE Base Code protocol
+    ///
+    /// Provides access to the PXE/DHCP network boot state,
+    /// and TFTP file downloads.
+    #[Protocol("03C4E603-AC28-11D3-9A2D-0090273FC14D", crate("crate"))]
+    PxeBaseCode(RawPxeBaseCode)
+);
+
+impl<'table> PxeBaseCode<'table> {
+    /// Enable the use of the PXE Base Code protocol
+    ///
+    /// If `ipv6` is true, IPv6 will be used instead of IPv4
+    pub fn start(&self, ipv6: bool) -> Result<()> {
+        let start = self.interface().start.ok_or(Status::UNSUPPORTED)?;
+        // Safety: Construction ensures these are valid
+        unsafe { (start)(self.interface, ipv6.into()) }.into()
+    }
+
+    /// Disable the use of the PXE Base Code protocol
+    pub fn stop(&self) -> Result<()> {
+        let stop = self.interface().stop.ok_or(Status::UNSUPPORTED)?;
+        // Safety: Construction ensures these are valid
+        unsafe { (stop)(self.interface) }.into()
+    }
+
+    /// Attempt to complete a DHCP sequence
+    ///
+    /// If `sort` is true, offers will be sorted according to the PXE
+    /// specification, otherwise the first valid offer is used.
+    pub fn dhcp(&self, sort: bool) -> Result<()> {
+        let dhcp = self.interface().dhcp.ok_or(Status::UNSUPPORTED)?;
+        // Safety: Construction ensures these are valid
+        unsafe { (dhcp)(self.interface, sort.into()) }.into()
+    }
+
+    /// The current mode of the protocol, or [`None`] if firmware
+    /// did not provide one.
+    pub fn mode(&self) -> Option<PxeMode<'_>> {
+        let mode = self.interface().mode;
+        if mode.is_null() {
+            return None;
+        }
+        // Safety: Firmware ensures `mode` is valid, checked for null above
+        Some(PxeMode {
+            mode: unsafe { &*mode },
+        })
+    }
+
+    /// Get the size of `file` on the TFTP server `server`, in bytes
+    ///
+    /// `file` must be ASCII and not contain any nul bytes.
+    pub fn tftp_file_size(&self, server: &IP, file: &str) -> Result<u64> {
+        let name = tftp_name(file)?;
+        let mut size = 0;
+        self.mtftp(
+            TftpOpcode::TFTP_GET_FILE_SIZE,
+            null_mut(),
+            &mut size,
+            server,
+            &name,
+        )
+        .map(|_| size)
+    }
+
+    /// Read `file` from the TFTP server `server` into `buf`
+    ///
+    /// Returns how many bytes were read.
+    ///
+    /// `file` must be ASCII and not contain any nul bytes.
+    ///
+    /// Returns [`Status::BUFFER_TOO_SMALL`] if `buf` could not hold the file
+    pub fn tftp_read_file(&self, server: &IP, file: &str, buf: &mut [u8]) -> Result<usize> {
+        let name = tftp_name(file)?;
+        let mut size = buf.len() as u64;
+        self.mtftp(
+            TftpOpcode::TFTP_READ_FILE,
+            buf.as_mut_ptr(),
+            &mut size,
+            server,
+            &name,
+        )?;
+        Ok(size as usize)
+    }
+
+    /// Read all of `file` from the TFTP server `server`
+    ///
+    /// See [`PxeBaseCode::tftp_read_file`]
+    pub fn tftp_read(&self, server: &IP, file: &str) -> Result<Vec<u8>> {
+        let size = self.tftp_file_size(server, file)?;
+        let size: usize = size.try_into().map_err(|_| Status::BAD_BUFFER_SIZE)?;
+        let mut buf = vec![0u8; size];
+        let read = self.tftp_read_file(server, file, &mut buf)?;
+        buf.truncate(read);
+        Ok(buf)
+    }
+}
+
+impl<'table> PxeBaseCode<'table> {
+    fn mtftp(
+        &self,
+        op: TftpOpcode,
+        buf: *mut u8,
+        size: &mut u64,
+        server: &IP,
+        name: &[u8],
+    ) -> Result<()> {
+        let mtftp = self.interface().mtftp.ok_or(Status::UNSUPPORTED)?;
+        // Safety: Construction ensures these are valid
+        // `name` is nul terminated, `buf` is valid for `size` bytes or null
+        unsafe {
+            (mtftp)(
+                self.interface,
+                op,
+                buf.cast(),
+                false.into(),
+                size,
+                null(),
+                server,
+                name.as_ptr(),
+                null(),
+                false.into(),
+            )
+        }
+        .into()
+    }
+}
+
+/// Convert `file` to a nul terminated ASCII string for TFTP
+fn tftp_name(file: &str) -> Result<Vec<u8>> {
+    if !file.is_ascii() || file.contains('\0') {
+        return Err(Status::INVALID_PARAMETER.into());
+    }
+    let mut name = Vec::with_capacity(file.len() + 1);
+    name.extend_from_slice(file.as_bytes());
+    name.push(0);
+    Ok(name)
+}
+
+/// The current mode of a [`PxeBaseCode`] protocol
+#[derive(Debug, Clone, Copy)]
+pub struct PxeMode<'a> {
+    mode: &'a RawPxeMode,
+}
+
+impl<'a> PxeMode<'a> {
+    /// Whether [`PxeBaseCode::start`] has been called
+    pub fn started(&self) -> bool {
+        self.mode.started.into()
+    }
+
+    /// Whether IPv6 is being used
+    pub fn using_ipv6(&self) -> bool {
+        self.mode.using_ipv6.into()
+    }
+
+    /// Our current IP address
+    pub fn station_ip(&self) -> IP {
+        self.mode.station_ip
+    }
+
+    /// Our current subnet mask
+    pub fn subnet_mask(&self) -> IP {
+        self.mode.subnet_mask
+    }
+
+    /// The raw DHCP ACK packet, or [`None`] if one has not been received
+    pub fn dhcp_ack(&self) -> Option<&'a [u8]> {
+        if self.mode.dhcp_ack_received.into() {
+            Some(&self.mode.dhcp_ack.raw)
+        } else {
+            None
+        }
+    }
+
+    /// The boot server IP from the DHCP ACK packet,
+    /// or [`None`] if one has not been received.
+    ///
+    /// This is only supported for IPv4, and is [`None`] if
+    /// [`PxeMode::using_ipv6`]
+    pub fn server_ip(&self) -> Option<IP> {
+        if self.using_ipv6() {
+            return None;
+        }
+        let ack = self.dhcp_ack()?;
+        let ip = &ack[DHCPV4_SERVER_IP..DHCPV4_SERVER_IP + 4];
+        Some(IP::from_ipv4(IPV4::new([ip[0], ip[1], ip[2], ip[3]])))
+    }
+}
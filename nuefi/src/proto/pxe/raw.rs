@@ -0,0 +1,128 @@
+//! Raw UEFI PXE Base Code Protocol types
+use core::ffi::c_void;
+
+use crate::nuefi_core::base::{Boolean, Status, IP};
+
+/// `EFI_PXE_BASE_CODE_TFTP_OPCODE`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct TftpOpcode(u32);
+
+impl TftpOpcode {
+    pub const TFTP_FIRST: Self = Self(0);
+    pub const TFTP_GET_FILE_SIZE: Self = Self(1);
+    pub const TFTP_READ_FILE: Self = Self(2);
+    pub const TFTP_WRITE_FILE: Self = Self(3);
+    pub const TFTP_READ_DIRECTORY: Self = Self(4);
+    pub const MTFTP_GET_FILE_SIZE: Self = Self(5);
+    pub const MTFTP_READ_FILE: Self = Self(6);
+    pub const MTFTP_READ_DIRECTORY: Self = Self(7);
+    pub const MTFTP_LAST: Self = Self(8);
+}
+
+/// `EFI_PXE_BASE_CODE_MTFTP_INFO`
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RawMtftpInfo {
+    pub multicast_ip: IP,
+    pub client_port: u16,
+    pub server_port: u16,
+    pub listen_timeout: u16,
+    pub transmit_timeout: u16,
+}
+
+/// `EFI_PXE_BASE_CODE_PACKET`
+///
+/// This is a union of the raw bytes, and DHCPv4/v6 packets.
+/// We only expose the raw bytes.
+#[derive(Clone, Copy)]
+#[repr(C, align(4))]
+pub struct RawPacket {
+    pub raw: [u8; 1472],
+}
+
+impl core::fmt::Debug for RawPacket {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RawPacket").finish_non_exhaustive()
+    }
+}
+
+/// `EFI_PXE_BASE_CODE_MODE`
+///
+/// # Note
+///
+/// This is only the prefix of the full structure, up to and including
+/// `pxe_bis_reply`.
+/// It must only ever be accessed through a pointer from firmware.
+#[derive(Debug)]
+#[repr(C)]
+pub struct RawPxeMode {
+    pub started: Boolean,
+    pub ipv6_available: Boolean,
+    pub ipv6_supported: Boolean,
+    pub using_ipv6: Boolean,
+    pub bis_supported: Boolean,
+    pub bis_detected: Boolean,
+    pub auto_arp: Boolean,
+    pub send_guid: Boolean,
+    pub dhcp_discover_valid: Boolean,
+    pub dhcp_ack_received: Boolean,
+    pub proxy_offer_received: Boolean,
+    pub pxe_discover_valid: Boolean,
+    pub pxe_reply_received: Boolean,
+    pub pxe_bis_reply_received: Boolean,
+    pub icmp_error_received: Boolean,
+    pub tftp_error_received: Boolean,
+    pub make_callbacks: Boolean,
+    pub ttl: u8,
+    pub tos: u8,
+    pub station_ip: IP,
+    pub subnet_mask: IP,
+    pub dhcp_discover: RawPacket,
+    pub dhcp_ack: RawPacket,
+    pub proxy_offer: RawPacket,
+    pub pxe_discover: RawPacket,
+    pub pxe_reply: RawPacket,
+    pub pxe_bis_reply: RawPacket,
+}
+
+pub type Start = unsafe extern "efiapi" fn(this: *mut RawPxeBaseCode, use_ipv6: Boolean) -> Status;
+
+pub type Stop = unsafe extern "efiapi" fn(this: *mut RawPxeBaseCode) -> Status;
+
+pub type Dhcp =
+    unsafe extern "efiapi" fn(this: *mut RawPxeBaseCode, sort_offers: Boolean) -> Status;
+
+pub type Mtftp = unsafe extern "efiapi" fn(
+    this: *mut RawPxeBaseCode,
+    operation: TftpOpcode,
+    buffer: *mut c_void,
+    overwrite: Boolean,
+    buffer_size: *mut u64,
+    block_size: *const usize,
+    server_ip: *const IP,
+    filename: *const u8,
+    info: *const RawMtftpInfo,
+    dont_use_buffer: Boolean,
+) -> Status;
+
+/// UEFI PXE Base Code protocol
+#[derive(Debug)]
+#[repr(C)]
+pub struct RawPxeBaseCode {
+    /// Currently `0x00010000`
+    pub revision: u64,
+    pub start: Option<Start>,
+    pub stop: Option<Stop>,
+    pub dhcp: Option<Dhcp>,
+    pub discover: *mut c_void,
+    pub mtftp: Option<Mtftp>,
+    pub udp_write: *mut c_void,
+    pub udp_read: *mut c_void,
+    pub set_ip_filter: *mut c_void,
+    pub arp: *mut c_void,
+    pub set_parameters: *mut c_void,
+    pub set_station_ip: *mut c_void,
+    pub set_packets: *mut c_void,
+    pub mode: *mut RawPxeMode,
+}
@@ -0,0 +1,78 @@
+//! UEFI Shell Protocol
+use alloc::string::String;
+use core::ptr::null;
+
+use raw::*;
+
+use crate::{
+    error::{Result, Status},
+    get_image_handle,
+    nuefi_core::base::{decode_ucs2, encode_ucs2},
+    proto::{Guid, Protocol},
+    string::UefiStr,
+    util::interface,
+    Protocol,
+};
+
+pub mod raw;
+
+interface!(
+    /// UEFI Shell protocol
+    ///
+    /// Available when running under the UEFI Shell,
+    /// and provides access to shell state and command execution.
+    #[Protocol("6302D008-7F9B-4F30-87AC-60C9FEF5DA4E", crate("crate"))]
+    Shell(RawShell)
+);
+
+impl<'table> Shell<'table> {
+    /// The shell version, as `(major, minor)`
+    pub fn version(&self) -> (u32, u32) {
+        (
+            self.interface().major_version,
+            self.interface().minor_version,
+        )
+    }
+
+    /// The current working directory, or [`None`] if there isn't one
+    pub fn get_cur_dir(&self) -> Result<Option<String>> {
+        let gc = self.interface().get_cur_dir.ok_or(Status::UNSUPPORTED)?;
+
+        // Safety: Construction ensures these are valid
+        // A null mapping means the current file system
+        let dir = unsafe { (gc)(null()) };
+        if dir.is_null() {
+            return Ok(None);
+        }
+        // Safety: Shell guarantees a non-null return is a valid nul terminated
+        // string, which we copy out of and do not free.
+        let dir = unsafe { UefiStr::from_ptr(dir.cast_mut()) };
+        Ok(Some(decode_ucs2(dir.as_slice())))
+    }
+
+    /// Set the current working directory to `dir`
+    ///
+    /// `dir` may include a file system mapping, such as `fs0:\efi`
+    pub fn set_cur_dir(&self, dir: &str) -> Result<()> {
+        let sc = self.interface().set_cur_dir.ok_or(Status::UNSUPPORTED)?;
+        let dir = encode_ucs2(dir);
+
+        // Safety: Construction ensures these are valid
+        // `dir` is nul terminated, null file system means the one in `dir`
+        unsafe { (sc)(null(), dir.as_ptr()) }.into()
+    }
+
+    /// Execute the shell command line `cmd`, as if typed at the prompt
+    ///
+    /// Returns the [`Status`] the command itself returned
+    pub fn execute(&self, cmd: &str) -> Result<Status> {
+        let ex = self.interface().execute.ok_or(Status::UNSUPPORTED)?;
+        let mut parent = get_image_handle().ok_or(Status::NOT_STARTED)?;
+        let cmd = encode_ucs2(cmd);
+        let mut code = Status::SUCCESS;
+
+        // Safety: Construction ensures these are valid
+        // `cmd` is nul terminated, a null environment means our current one
+        unsafe { (ex)(&mut parent, cmd.as_ptr(), null(), &mut code) }.map_success(|| code)
+    }
+}
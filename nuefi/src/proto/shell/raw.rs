@@ -0,0 +1,71 @@
+//! Raw UEFI Shell Protocol types
+use core::ffi::c_void;
+
+use crate::{
+    nuefi_core::base::{Char16, Event, Status},
+    EfiHandle,
+};
+
+pub type Execute = unsafe extern "efiapi" fn(
+    parent_image: *mut EfiHandle,
+    command_line: *const Char16,
+    environment: *const *const Char16,
+    status_code: *mut Status,
+) -> Status;
+
+pub type GetCurDir = unsafe extern "efiapi" fn(file_system_mapping: *const Char16) -> *const Char16;
+
+pub type SetCurDir =
+    unsafe extern "efiapi" fn(file_system: *const Char16, dir: *const Char16) -> Status;
+
+/// UEFI Shell protocol
+///
+/// # Note
+///
+/// The fields added in Shell 2.1 and later are not included
+#[derive(Debug)]
+#[repr(C)]
+pub struct RawShell {
+    pub execute: Option<Execute>,
+    pub get_env: *mut c_void,
+    pub set_env: *mut c_void,
+    pub get_alias: *mut c_void,
+    pub set_alias: *mut c_void,
+    pub get_help_text: *mut c_void,
+    pub get_device_path_from_map: *mut c_void,
+    pub get_map_from_device_path: *mut c_void,
+    pub get_device_path_from_file_path: *mut c_void,
+    pub get_file_path_from_device_path: *mut c_void,
+    pub set_map: *mut c_void,
+    pub get_cur_dir: Option<GetCurDir>,
+    pub set_cur_dir: Option<SetCurDir>,
+    pub open_file_list: *mut c_void,
+    pub free_file_list: *mut c_void,
+    pub remove_dup_in_file_list: *mut c_void,
+    pub batch_is_active: *mut c_void,
+    pub is_root_shell: *mut c_void,
+    pub enable_page_break: *mut c_void,
+    pub disable_page_break: *mut c_void,
+    pub get_page_break: *mut c_void,
+    pub get_device_name: *mut c_void,
+    pub get_file_info: *mut c_void,
+    pub set_file_info: *mut c_void,
+    pub open_file_by_name: *mut c_void,
+    pub close_file: *mut c_void,
+    pub create_file: *mut c_void,
+    pub read_file: *mut c_void,
+    pub write_file: *mut c_void,
+    pub delete_file: *mut c_void,
+    pub delete_file_by_name: *mut c_void,
+    pub get_file_position: *mut c_void,
+    pub set_file_position: *mut c_void,
+    pub flush_file: *mut c_void,
+    pub find_files: *mut c_void,
+    pub find_files_in_dir: *mut c_void,
+    pub get_file_size: *mut c_void,
+    pub open_root: *mut c_void,
+    pub open_root_by_handle: *mut c_void,
+    pub execution_break: Event,
+    pub major_version: u32,
+    pub minor_version: u32,
+}
@@ -0,0 +1,85 @@
+//! UEFI Timestamp Protocol
+use core::time::Duration;
+
+use raw::*;
+
+use crate::{
+    error::{Result, Status},
+    proto::{Guid, Protocol},
+    util::interface,
+    Protocol,
+};
+
+pub mod raw;
+
+interface!(
+    /// UEFI Timestamp protocol
+    ///
+    /// Provides a platform independent high resolution timestamp counter.
+    ///
+    /// Unlike [`BootServices::next_monotonic_count`][count],
+    /// the counter frequency is known, so elapsed time can be computed.
+    ///
+    /// [count]: crate::table::BootServices::next_monotonic_count
+    #[Protocol("AFBFDE41-2E6E-4262-BA65-62B9236E5495", crate("crate"))]
+    Timestamp(RawTimestamp)
+);
+
+impl<'table> Timestamp<'table> {
+    /// The current value of the timestamp counter
+    ///
+    /// See [`TimestampProperties`] for how to interpret it
+    pub fn get_timestamp(&self) -> Result<u64> {
+        let gt = self.interface().get_timestamp.ok_or(Status::UNSUPPORTED)?;
+        // Safety: Construction ensures these are valid
+        Ok(unsafe { (gt)() })
+    }
+
+    /// The [`TimestampProperties`] of the counter
+    pub fn get_properties(&self) -> Result<TimestampProperties> {
+        let gp = self.interface().get_properties.ok_or(Status::UNSUPPORTED)?;
+        let mut props = RawTimestampProperties::default();
+        // Safety: Construction ensures these are valid
+        unsafe { (gp)(&mut props) }.map_success(|| TimestampProperties { props })
+    }
+}
+
+/// Properties of the [`Timestamp`] counter
+#[derive(Debug, Clone, Copy)]
+pub struct TimestampProperties {
+    props: RawTimestampProperties,
+}
+
+impl TimestampProperties {
+    /// Counter frequency, in Hz
+    pub fn frequency(&self) -> u64 {
+        self.props.frequency
+    }
+
+    /// The value the counter wraps around to zero after
+    pub fn end_value(&self) -> u64 {
+        self.props.end_value
+    }
+
+    /// Time elapsed between the counter values `start` and `end`,
+    /// accounting for the counter wrapping around at most once.
+    ///
+    /// Returns [`None`] if the frequency is zero.
+    pub fn elapsed(&self, start: u64, end: u64) -> Option<Duration> {
+        let freq = self.frequency();
+        if freq == 0 {
+            return None;
+        }
+        let ticks = if end >= start {
+            end - start
+        } else {
+            self.end_value()
+                .saturating_sub(start)
+                .saturating_add(end)
+                .saturating_add(1)
+        };
+        let secs = ticks / freq;
+        let nanos = (ticks % freq) as u128 * 1_000_000_000 / freq as u128;
+        Some(Duration::new(secs, nanos as u32))
+    }
+}
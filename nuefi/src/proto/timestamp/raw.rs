@@ -0,0 +1,26 @@
+//! Raw UEFI Timestamp Protocol types
+use crate::nuefi_core::base::Status;
+
+pub type GetTimestamp = unsafe extern "efiapi" fn() -> u64;
+
+pub type GetProperties =
+    unsafe extern "efiapi" fn(properties: *mut RawTimestampProperties) -> Status;
+
+/// `EFI_TIMESTAMP_PROPERTIES`
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C)]
+pub struct RawTimestampProperties {
+    /// Timestamp counter frequency, in Hz
+    pub frequency: u64,
+
+    /// The value the counter wraps around to zero after
+    pub end_value: u64,
+}
+
+/// UEFI Timestamp protocol
+#[derive(Debug)]
+#[repr(C)]
+pub struct RawTimestamp {
+    pub get_timestamp: Option<GetTimestamp>,
+    pub get_properties: Option<GetProperties>,
+}
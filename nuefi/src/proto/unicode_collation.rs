@@ -0,0 +1,84 @@
+//! UEFI Unicode Collation Protocol
+use alloc::string::String;
+use core::{cmp::Ordering, ffi::CStr};
+
+use raw::*;
+
+use crate::{
+    error::{Result, Status},
+    nuefi_core::base::{decode_ucs2, encode_ucs2},
+    proto::{Guid, Protocol},
+    util::interface,
+    Protocol,
+};
+
+pub mod raw;
+
+interface!(
+    /// UEFI Unicode Collation 2 protocol
+    ///
+    /// Provides language aware, case-insensitive string comparison.
+    ///
+    /// This is the correct way to compare UEFI file names,
+    /// which are case-insensitive on FAT.
+    #[Protocol("A4C751FC-23AE-4C3E-92E9-4964CF63F349", crate("crate"))]
+    UnicodeCollation(RawUnicodeCollation)
+);
+
+impl<'table> UnicodeCollation<'table> {
+    /// The RFC 4646 language codes this protocol supports,
+    /// separated by `;`, or [`None`] if unavailable.
+    pub fn supported_languages(&self) -> Option<&str> {
+        let langs = self.interface().supported_languages;
+        if langs.is_null() {
+            return None;
+        }
+        // Safety: Firmware ensures this is a nul terminated ASCII string,
+        // checked for null above
+        unsafe { CStr::from_ptr(langs.cast()) }.to_str().ok()
+    }
+
+    /// Case-insensitive comparison of `a` and `b`
+    pub fn stri_coll(&self, a: &str, b: &str) -> Result<Ordering> {
+        let sc = self.interface().stri_coll.ok_or(Status::UNSUPPORTED)?;
+        let a = encode_ucs2(a);
+        let b = encode_ucs2(b);
+        // Safety: Construction ensures these are valid, `a` and `b` are nul
+        // terminated
+        let ret = unsafe { (sc)(self.interface, a.as_ptr(), b.as_ptr()) };
+        Ok(ret.cmp(&0))
+    }
+
+    /// Case-insensitive check whether `string` matches `pattern`
+    ///
+    /// `pattern` supports the wildcards `*`, `?`, and `[]` character sets.
+    pub fn metai_match(&self, pattern: &str, string: &str) -> Result<bool> {
+        let mm = self.interface().metai_match.ok_or(Status::UNSUPPORTED)?;
+        let pattern = encode_ucs2(pattern);
+        let string = encode_ucs2(string);
+        // Safety: Construction ensures these are valid, `pattern` and
+        // `string` are nul terminated
+        let ret = unsafe { (mm)(self.interface, string.as_ptr(), pattern.as_ptr()) };
+        Ok(ret.into())
+    }
+
+    /// `s` converted to lower case
+    pub fn str_lwr(&self, s: &str) -> Result<String> {
+        let sl = self.interface().str_lwr.ok_or(Status::UNSUPPORTED)?;
+        let mut s = encode_ucs2(s);
+        // Safety: Construction ensures these are valid, `s` is nul
+        // terminated
+        unsafe { (sl)(self.interface, s.as_mut_ptr()) };
+        Ok(decode_ucs2(&s[..s.len() - 1]))
+    }
+
+    /// `s` converted to upper case
+    pub fn str_upr(&self, s: &str) -> Result<String> {
+        let su = self.interface().str_upr.ok_or(Status::UNSUPPORTED)?;
+        let mut s = encode_ucs2(s);
+        // Safety: Construction ensures these are valid, `s` is nul
+        // terminated
+        unsafe { (su)(self.interface, s.as_mut_ptr()) };
+        Ok(decode_ucs2(&s[..s.len() - 1]))
+    }
+}
@@ -0,0 +1,48 @@
+//! Raw UEFI Unicode Collation Protocol types
+use crate::nuefi_core::base::{Boolean, Char16, Char8};
+
+pub type StriColl = unsafe extern "efiapi" fn(
+    this: *mut RawUnicodeCollation,
+    s1: *const Char16,
+    s2: *const Char16,
+) -> isize;
+
+pub type MetaiMatch = unsafe extern "efiapi" fn(
+    this: *mut RawUnicodeCollation,
+    string: *const Char16,
+    pattern: *const Char16,
+) -> Boolean;
+
+pub type StrLwr = unsafe extern "efiapi" fn(this: *mut RawUnicodeCollation, string: *mut Char16);
+
+pub type StrUpr = unsafe extern "efiapi" fn(this: *mut RawUnicodeCollation, string: *mut Char16);
+
+pub type FatToStr = unsafe extern "efiapi" fn(
+    this: *mut RawUnicodeCollation,
+    fat_size: usize,
+    fat: *const Char8,
+    string: *mut Char16,
+);
+
+pub type StrToFat = unsafe extern "efiapi" fn(
+    this: *mut RawUnicodeCollation,
+    string: *const Char16,
+    fat_size: usize,
+    fat: *mut Char8,
+) -> Boolean;
+
+/// UEFI Unicode Collation protocol
+#[derive(Debug)]
+#[repr(C)]
+pub struct RawUnicodeCollation {
+    pub stri_coll: Option<StriColl>,
+    pub metai_match: Option<MetaiMatch>,
+    pub str_lwr: Option<StrLwr>,
+    pub str_upr: Option<StrUpr>,
+    pub fat_to_str: Option<FatToStr>,
+    pub str_to_fat: Option<StrToFat>,
+
+    /// Nul terminated ASCII list of RFC 4646 language codes,
+    /// separated by `;`
+    pub supported_languages: *const Char8,
+}
@@ -2,7 +2,14 @@
 //!
 //! Note: This crate treats all UEFI strings as UTF-16
 use alloc::{string::String, vec::Vec};
-use core::{fmt::Display, marker::PhantomData, mem::transmute, ops::Deref, slice::from_raw_parts};
+use core::{
+    char::DecodeUtf16Error,
+    fmt::Display,
+    marker::PhantomData,
+    mem::{align_of, size_of, transmute},
+    ops::Deref,
+    slice::from_raw_parts,
+};
 
 use log::{error, trace};
 
@@ -10,11 +17,16 @@ use crate::{
     error::{Result, Status},
     get_boot_table,
     mem::MemoryType,
+    nuefi_core::{
+        base::decode_ucs2,
+        proto::device_path::{DevicePathSubType, DevicePathType},
+    },
     proto::{
-        device_path::{DevicePath, DevicePathToText, DevicePathUtil},
+        device_path::{raw::RawDevicePath, DevicePath, DevicePathToText, DevicePathUtil},
         Scope,
     },
     Boot,
+    table::BootServices,
     SystemTable,
 };
 
@@ -236,6 +248,14 @@ impl<'buf> UefiStr<'buf> {
         unsafe { from_raw_parts(self.data, self.len) }
     }
 
+    /// Iterator over the [`char`]s of this string, without allocating
+    ///
+    /// Unpaired surrogates are returned as [`DecodeUtf16Error`]s
+    #[inline]
+    pub fn chars(&self) -> impl Iterator<Item = core::result::Result<char, DecodeUtf16Error>> + '_ {
+        char::decode_utf16(self.as_slice().iter().copied())
+    }
+
     /// Convert the [`UefiString`] into a [`String`]
     ///
     /// # Panics
@@ -254,6 +274,32 @@ impl<'buf> Display for UefiStr<'buf> {
     }
 }
 
+/// Compares UTF-16 code units, without allocating
+impl<'buf> PartialEq<str> for UefiStr<'buf> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_slice().iter().copied().eq(other.encode_utf16())
+    }
+}
+
+impl<'buf, 'a> PartialEq<&'a str> for UefiStr<'buf> {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+/// Compares UTF-16 code units, without allocating
+impl<'table> PartialEq<str> for UefiString<'table> {
+    fn eq(&self, other: &str) -> bool {
+        self.ref_ == *other
+    }
+}
+
+impl<'table, 'a> PartialEq<&'a str> for UefiString<'table> {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.ref_ == **other
+    }
+}
+
 /// An unowned UEFI [DevicePath]
 #[derive(Debug)]
 pub struct Path<'table> {
@@ -267,33 +313,104 @@ impl<'table> Path<'table> {
     }
 
     /// Convert [`Path`] to [`PathBuf`]
+    ///
+    /// This uses the global boot table, see [`Path::to_path_buf_in`]
+    /// to pass it explicitly.
     pub fn to_path_buf(&self) -> Result<PathBuf<'table>> {
-        let copy = self.data.duplicate()?;
+        let table = get_boot_table().ok_or(Status::DEVICE_ERROR)?;
+        self.to_path_buf_in(&table.boot())
+    }
+
+    /// Convert [`Path`] to [`PathBuf`], using `boot`
+    pub fn to_path_buf_in(&self, boot: &BootServices) -> Result<PathBuf<'table>> {
+        let copy = self.data.duplicate_in(boot)?;
         let v = PathBuf::new(copy);
         Ok(v)
     }
 
+    /// Join the `\` separated file path `rel` onto this path,
+    /// returning a new [`PathBuf`]
+    ///
+    /// Each component of `rel` is appended as a Media File Path node.
+    /// Empty components are skipped.
+    ///
+    /// This uses the global boot table, see [`Path::join_in`]
+    /// to pass it explicitly.
+    ///
+    /// # Example
+    ///
+    /// `dir.join("subdir\\file.efi")`
+    pub fn join(&self, rel: &str) -> Result<PathBuf<'table>> {
+        let table = get_boot_table().ok_or(Status::DEVICE_ERROR)?;
+        self.join_in(&table.boot(), rel)
+    }
+
+    /// Join the `\` separated file path `rel` onto this path using `boot`,
+    /// returning a new [`PathBuf`]
+    ///
+    /// See [`Path::join`]
+    pub fn join_in(&self, boot: &BootServices, rel: &str) -> Result<PathBuf<'table>> {
+        let mut path = self.to_path_buf_in(boot)?;
+        for component in rel.split('\\').filter(|c| !c.is_empty()) {
+            // The previous path is freed on drop
+            path = PathBuf::new(path.data.append_file_path_in(boot, component)?);
+        }
+        Ok(path)
+    }
+
     /// Convert this path to a UEFI String
-    pub fn to_text(&'table self) -> Result<UefiString<'table>> {
-        self.data.to_uefi_string()
+    ///
+    /// The returned [`UefiString`] owns its own buffer, and may outlive
+    /// this [`Path`].
+    pub fn to_text(&self) -> Result<UefiString<'table>> {
+        let table = get_boot_table().ok_or(Status::DEVICE_ERROR)?;
+        self.data.to_uefi_string_in(&table.boot())
     }
 
     /// Convert this path to a Rust String
     ///
     /// Invalid characters are mapped to [`char::REPLACEMENT_CHARACTER`]
     pub fn to_string_lossy(&self) -> Result<String> {
-        let s = self.data.to_uefi_string()?;
-        let s = s.as_slice();
-        let s = char::decode_utf16(s.iter().copied())
-            .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER))
-            .collect::<String>();
-        Ok(s)
+        let s = self.to_text()?;
+        Ok(decode_ucs2(s.as_slice()))
     }
 
     /// Get this as a [DevicePath]
     pub fn as_device(&self) -> &DevicePath<'table> {
         &self.data
     }
+
+    /// Iterator over the file path components of this path
+    ///
+    /// This yields the path string of each Media File Path node,
+    /// skipping all other nodes.
+    /// File Path nodes that are malformed, or not aligned for UCS-2,
+    /// are skipped too.
+    pub fn components(&self) -> impl Iterator<Item = UefiStr<'_>> + '_ {
+        let hdr = size_of::<RawDevicePath>();
+        self.data.nodes().filter_map(move |node| {
+            // Safety: `nodes` ensures every node is valid for its header.
+            // The header is packed, so alignment doesn't matter.
+            let n = unsafe { &*node };
+            if n.ty != DevicePathType::MEDIA || n.sub_ty != DevicePathSubType::MEDIA_FILE {
+                return None;
+            }
+            let len = (u16::from_le_bytes(n.len) as usize - hdr) / 2;
+            // Safety: `nodes` ensures the node is valid for `n.len` bytes
+            let data = unsafe { node.cast::<u8>().add(hdr) } as *mut u16;
+            if len == 0 || data.align_offset(align_of::<u16>()) != 0 {
+                return None;
+            }
+            // Safety: Checked above that `data` is aligned,
+            // and valid for `len` characters
+            let path = unsafe { UefiStr::from_ptr_len(data, len) };
+            // `UefiStr` requires a nul terminator
+            if path.as_slice_with_nul()[len - 1] != 0 {
+                return None;
+            }
+            Some(path)
+        })
+    }
 }
 
 impl<'table> Display for Path<'table> {
@@ -331,7 +448,8 @@ impl<'table> PathBuf<'table> {
     }
 
     pub fn try_clone(&self) -> Result<Self> {
-        Ok(PathBuf::new(self.data.duplicate()?))
+        let table = get_boot_table().ok_or(Status::DEVICE_ERROR)?;
+        Ok(PathBuf::new(self.data.duplicate_in(&table.boot())?))
     }
 }
 
@@ -363,3 +481,74 @@ pub(crate) const unsafe fn string_len(data: *const u16) -> usize {
     }
     len
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::device_path::DevicePath;
+
+    /// The string returned from [`Path::to_text`] must not borrow the [`Path`]
+    ///
+    /// This is a compile time test, it never needs to run.
+    #[test]
+    fn to_text_outlives_path() {
+        fn _check<'table>(dev: DevicePath<'table>) -> Result<UefiString<'table>> {
+            let s = {
+                let path = Path::new(dev);
+                path.to_text()?
+            };
+            Ok(s)
+        }
+    }
+
+    #[test]
+    fn eq_str() {
+        let mut data: Vec<u16> = "héllo 🦀".encode_utf16().chain([0]).collect();
+        // Safety: `data` is nul terminated and outlives `s`
+        let s = unsafe { UefiStr::from_ptr_len(data.as_mut_ptr(), data.len()) };
+        assert!(s == "héllo 🦀");
+        assert!(s != "héllo");
+        assert!(s != "héllo 🦀!");
+        assert!(s != "");
+    }
+
+    #[test]
+    fn chars() {
+        let mut data: Vec<u16> = "a\\🦀".encode_utf16().chain([0xD800, 0]).collect();
+        // Safety: `data` is nul terminated and outlives `s`
+        let s = unsafe { UefiStr::from_ptr_len(data.as_mut_ptr(), data.len()) };
+        let mut chars = s.chars();
+        assert_eq!(chars.next(), Some(Ok('a')));
+        assert_eq!(chars.next(), Some(Ok('\\')));
+        assert_eq!(chars.next(), Some(Ok('🦀')));
+        assert!(matches!(chars.next(), Some(Err(_))));
+        assert_eq!(chars.next(), None);
+    }
+
+    #[test]
+    fn components() {
+        let mut bytes: Vec<u8> = Vec::new();
+        // An ACPI node, which is skipped
+        bytes.extend([0x02, 0x01, 12, 0]);
+        bytes.extend([0; 8]);
+        for name in ["EFI", "BOOT"] {
+            let name: Vec<u16> = name.encode_utf16().chain([0]).collect();
+            bytes.extend([0x04, 0x04, (4 + name.len() * 2) as u8, 0]);
+            bytes.extend(name.iter().flat_map(|c| c.to_le_bytes()));
+        }
+        bytes.extend([0x7F, 0xFF, 4, 0]);
+
+        // Copy into a `u16` buffer for alignment
+        let mut data: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        // Safety: `data` is a valid device path that outlives `path`
+        let path = Path::new(unsafe { DevicePath::new(data.as_mut_ptr().cast()) });
+
+        let mut parts = path.components();
+        assert!(parts.next().unwrap() == "EFI");
+        assert!(parts.next().unwrap() == "BOOT");
+        assert!(parts.next().is_none());
+    }
+}
@@ -3,10 +3,11 @@
 use alloc::{string::String, vec::Vec};
 use core::{
     ffi::c_void,
+    fmt,
     iter::from_fn,
     marker::PhantomData,
     mem::{size_of, transmute},
-    ptr::{null_mut, NonNull},
+    ptr::{null, null_mut, NonNull},
     slice::from_raw_parts,
     time::Duration,
 };
@@ -16,7 +17,8 @@ pub use nuefi_core::table::config;
 use crate::{
     error::{Result, Status},
     get_image_handle,
-    mem::MemoryType,
+    mem::{MemoryDescriptor, MemoryMap, MemoryType},
+    nuefi_core::base::{encode_ucs2, Event, TaskPriorityLevel},
     proto::{
         self,
         console::SimpleTextOutput,
@@ -36,8 +38,12 @@ pub mod raw {
         boot_fn::*,
         config::ConfigurationTable as RawConfigurationTable,
         BootServices as RawBootServices,
+        FirmwareRevision,
         Header,
+        InterfaceType,
+        CRC,
         LocateSearch,
+        OpenProtocolInformationEntry as RawOpenInfo,
         Revision,
         RuntimeServices as RawRuntimeServices,
         SystemTable as RawSystemTable,
@@ -45,6 +51,9 @@ pub mod raw {
 }
 use raw::*;
 
+/// Offset of [`Header::crc32`]
+const HEADER_CRC_OFFSET: usize = core::mem::offset_of!(Header, crc32);
+
 interface!(
     /// The UEFI Boot Services
     BootServices(RawBootServices),
@@ -60,7 +69,7 @@ impl<'table> BootServices<'table> {
     unsafe fn locate_handle(
         &self,
         search: LocateSearch,
-        search_key: *mut c_void,
+        search_key: *const c_void,
         guid: *const Guid,
     ) -> Result<Vec<EfiHandle>> {
         let lh = self.interface().locate_handle.ok_or(Status::UNSUPPORTED)?;
@@ -96,9 +105,8 @@ impl<'table> BootServices<'table> {
 
     /// Load an image from memory `src`, returning its handle.
     ///
-    /// Note that this will return [`Ok`] on a [`Status::SECURITY_VIOLATION`].
-    ///
-    /// This case will need to be handled in [`BootServices::start_image`]
+    /// Note that this will return [`Ok`] on a [`Status::SECURITY_VIOLATION`],
+    /// see [`LoadedImageHandle::security_violation`].
     ///
     /// # Safety
     ///
@@ -110,7 +118,7 @@ impl<'table> BootServices<'table> {
         parent: EfiHandle,
         src: *mut c_void,
         src_len: usize,
-    ) -> Result<EfiHandle> {
+    ) -> Result<LoadedImageHandle> {
         let mut out = EfiHandle::null();
         let li = self.interface().load_image.ok_or(Status::UNSUPPORTED)?;
 
@@ -120,7 +128,53 @@ impl<'table> BootServices<'table> {
 
         if ret.is_success() || ret == Status::SECURITY_VIOLATION {
             assert_ne!(out, EfiHandle::null());
-            Ok(out)
+            Ok(LoadedImageHandle {
+                handle: out,
+                security_violation: ret == Status::SECURITY_VIOLATION,
+            })
+        } else {
+            Err(ret.into())
+        }
+    }
+
+    /// Open the protocol on `handle` for `controller` with `attributes`,
+    /// returning a [`Scope`] over it.
+    ///
+    /// If the protocol is unsupported, [`None`] is returned.
+    fn open_protocol_impl<'boot, Proto: proto::Protocol<'boot>>(
+        &'boot self,
+        handle: EfiHandle,
+        controller: Option<EfiHandle>,
+        attributes: u32,
+    ) -> Result<Option<Scope<'boot, Proto>>> {
+        let mut out: *mut c_void = null_mut();
+        let mut guid = Proto::GUID;
+        let op = self.interface().open_protocol.ok_or(Status::UNSUPPORTED)?;
+        let agent = get_image_handle().expect("UEFI Image Handle was null in open_protocol");
+
+        // Safety: Construction ensures safety. Statically verified arguments.
+        let ret = unsafe {
+            (op)(
+                handle,
+                &mut guid,
+                &mut out,
+                agent,
+                controller.unwrap_or(EfiHandle::null()),
+                attributes,
+            )
+        };
+        if ret.is_success() {
+            // Safety: Success means out is valid
+            unsafe {
+                Ok(Some(Scope::new(
+                    Proto::from_raw(out as *mut Proto::Raw),
+                    handle,
+                    agent,
+                    controller,
+                )))
+            }
+        } else if ret == Status::UNSUPPORTED {
+            Ok(None)
         } else {
             Err(ret.into())
         }
@@ -135,8 +189,6 @@ impl<'table> BootServices<'table> {
     /// `controller` is the controller handle that requires `Proto`
     ///
     /// The protocol is opened in Exclusive mode
-    // TODO: / FIXME This method is actually incompatible with drivers. Have two separate
-    // ones
     // TODO: Is this safe/sound to call with the same protocol twice?
     // Do we need to test the protocol first?
     // *Seems* to be fine, in qemu?
@@ -150,7 +202,7 @@ impl<'table> BootServices<'table> {
     pub fn all_handles(&self) -> Result<Vec<EfiHandle>> {
         // Safety: Statically correct for this call
         // All parameters are ignored for ALL_HANDLES
-        unsafe { self.locate_handle(LocateSearch::ALL_HANDLES, null_mut(), null_mut()) }
+        unsafe { self.locate_handle(LocateSearch::ALL_HANDLES, null(), null()) }
     }
 
     /// Get every handle that support the [`Protocol`]
@@ -160,7 +212,21 @@ impl<'table> BootServices<'table> {
         let guid = Proto::GUID;
         // Safety: Statically correct for this call
         // `search_key` is ignored for BY_PROTOCOL
-        unsafe { self.locate_handle(LocateSearch::BY_PROTOCOL, null_mut(), &guid) }
+        unsafe { self.locate_handle(LocateSearch::BY_PROTOCOL, null(), &guid) }
+    }
+
+    /// Get every handle that has had a protocol installed since the
+    /// last call for `registration`
+    ///
+    /// # Safety
+    ///
+    /// - `registration` must be a registration key from
+    ///   `RegisterProtocolNotify`
+    pub unsafe fn handles_for_registration(
+        &self,
+        registration: *const c_void,
+    ) -> Result<Vec<EfiHandle>> {
+        self.locate_handle(LocateSearch::BY_REGISTER_NOTIFY, registration, null())
     }
 
     /// Get an arbitrary handle that supports [`Protocol`]
@@ -178,6 +244,10 @@ impl<'table> BootServices<'table> {
     /// This will exclusively open the protocol.
     /// See [`BootServices::open_protocol`] for caveats.
     ///
+    /// If you only need to read from the protocol, such as querying
+    /// [`GraphicsOutput`][crate::proto::graphics::GraphicsOutput] modes,
+    /// use [`BootServices::inspect_protocol`] instead.
+    ///
     /// If the protocol is unsupported, [`None`] is returned.
     pub fn get_protocol<'boot, Protocol: proto::Protocol<'boot>>(
         &'boot self,
@@ -185,6 +255,27 @@ impl<'table> BootServices<'table> {
         self.open_protocol::<Protocol>(self.handle_for::<Protocol>()?)
     }
 
+    /// Find and return the first protocol instance found,
+    /// without stopping any drivers using it.
+    ///
+    /// Unlike [`BootServices::get_protocol`], this does not open the protocol
+    /// exclusively, so it will not disconnect, for example, your console.
+    ///
+    /// # Warning
+    ///
+    /// Because the protocol is not opened exclusively,
+    /// other drivers may be using it at the same time.
+    /// This is intended for reading information from a protocol,
+    /// changing its state may interfere with its other users.
+    ///
+    /// If the protocol is unsupported, [`None`] is returned.
+    pub fn inspect_protocol<'boot, Protocol: proto::Protocol<'boot>>(
+        &'boot self,
+    ) -> Result<Option<Scope<'boot, Protocol>>> {
+        // GET_PROTOCOL
+        self.open_protocol_impl::<Protocol>(self.handle_for::<Protocol>()?, None, 0x02)
+    }
+
     /// Find and return the first protocol instance found
     ///
     /// This finds the first handle that supports the requested protocol,
@@ -231,6 +322,30 @@ impl<'table> BootServices<'table> {
         }
     }
 
+    /// Find the first protocol instance found, and the handle it is on
+    ///
+    /// Like [`BootServices::locate_protocol`], but also returns the
+    /// [`EfiHandle`], for use with [`BootServices::open_protocol`] and
+    /// [`BootServices::close_protocol`].
+    ///
+    /// If no protocol is found, [`None`] is returned.
+    ///
+    /// # Safety
+    ///
+    /// See [`BootServices::handle_protocol`]
+    pub unsafe fn locate_protocol_with_handle<'boot, Protocol: proto::Protocol<'boot>>(
+        &'boot self,
+    ) -> Result<Option<(EfiHandle, Protocol)>> {
+        let handle = match self.handle_for::<Protocol>() {
+            Ok(h) => h,
+            Err(e) if e.status() == Status::NOT_FOUND => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        // Safety: Caller
+        let proto = unsafe { self.handle_protocol::<Protocol>(handle)? };
+        Ok(proto.map(|p| (handle, p)))
+    }
+
     /// Exclusively open a protocol on `handle` if it exists,
     /// returning a [`Scope`] over the requested protocol.
     ///
@@ -256,25 +371,63 @@ impl<'table> BootServices<'table> {
         &'boot self,
         handle: EfiHandle,
     ) -> Result<Option<Scope<Proto>>> {
-        let mut out: *mut c_void = null_mut();
+        self.open_protocol_impl(handle, None, 0x20)
+    }
+
+    /// Open the protocol on `handle` on behalf of a driver managing
+    /// `controller`, returning a [`Scope`] over it.
+    ///
+    /// This is for use by drivers, in `EFI_DRIVER_BINDING_PROTOCOL.Start`.
+    /// The agent is your image handle,
+    /// which should have the `EFI_DRIVER_BINDING_PROTOCOL` installed on it.
+    ///
+    /// The protocol is opened `BY_DRIVER`,
+    /// and closed for `controller` when the [`Scope`] is dropped.
+    ///
+    /// If the protocol is unsupported, [`None`] is returned.
+    ///
+    /// Returns [`Status::ALREADY_STARTED`] if this driver already has
+    /// the protocol open for `controller`,
+    /// and [`Status::ACCESS_DENIED`] if another driver does.
+    pub fn open_protocol_by_driver<'boot, Proto: proto::Protocol<'boot>>(
+        &'boot self,
+        handle: EfiHandle,
+        controller: EfiHandle,
+    ) -> Result<Option<Scope<'boot, Proto>>> {
+        self.open_protocol_impl(handle, Some(controller), 0x10)
+    }
+
+    /// Check whether `handle` supports `Proto`, without opening it
+    ///
+    /// This uses the `TEST_PROTOCOL` attribute, so no interface is returned,
+    /// and other agents using the protocol are not disturbed.
+    ///
+    /// This is the preferred way to check for a protocol, instead of
+    /// [`BootServices::handle_protocol`].
+    pub fn supports_protocol<'boot, Proto: proto::Protocol<'boot>>(
+        &self,
+        handle: EfiHandle,
+    ) -> Result<bool> {
         let mut guid = Proto::GUID;
         let op = self.interface().open_protocol.ok_or(Status::UNSUPPORTED)?;
-        let agent = get_image_handle().expect("UEFI Image Handle was null in open_protocol");
+        let agent = get_image_handle().ok_or(Status::NOT_STARTED)?;
 
         // Safety: Construction ensures safety. Statically verified arguments.
-        let ret = unsafe { (op)(handle, &mut guid, &mut out, agent, EfiHandle::null(), 0x20) };
+        // `TEST_PROTOCOL` ignores the interface pointer, and allows null.
+        let ret = unsafe {
+            (op)(
+                handle,
+                &mut guid,
+                null_mut(),
+                agent,
+                EfiHandle::null(),
+                0x04,
+            )
+        };
         if ret.is_success() {
-            // Safety: Success means out is valid
-            unsafe {
-                Ok(Some(Scope::new(
-                    Proto::from_raw(out as *mut Proto::Raw),
-                    handle,
-                    agent,
-                    None,
-                )))
-            }
+            Ok(true)
         } else if ret == Status::UNSUPPORTED {
-            Ok(None)
+            Ok(false)
         } else {
             Err(ret.into())
         }
@@ -305,6 +458,46 @@ impl<'table> BootServices<'table> {
         .into()
     }
 
+    /// Get information on every agent that currently has `Proto` open
+    /// on `handle`
+    ///
+    /// This is useful to find out who is holding a protocol,
+    /// such as when [`BootServices::open_protocol`] fails with
+    /// [`Status::ACCESS_DENIED`].
+    pub fn open_protocol_information<'boot, Proto: proto::Protocol<'boot>>(
+        &self,
+        handle: EfiHandle,
+    ) -> Result<Vec<OpenInfo>> {
+        let mut guid = Proto::GUID;
+        let mut out: *mut RawOpenInfo = null_mut();
+        let mut count = 0;
+        let opi = self
+            .interface()
+            .open_protocol_information
+            .ok_or(Status::UNSUPPORTED)?;
+
+        // Safety: Construction ensures safety. Statically verified arguments.
+        let ret = unsafe { (opi)(handle, &mut guid, &mut out, &mut count) };
+        if !ret.is_success() {
+            return Err(ret.into());
+        }
+        if out.is_null() {
+            return Ok(Vec::new());
+        }
+
+        // Safety: Success means `out` is valid for `count` entries
+        let info = unsafe { from_raw_parts(out, count) }
+            .iter()
+            .copied()
+            .map(OpenInfo::new)
+            .collect();
+
+        // Safety: `out` was allocated by UEFI, and is non-null
+        unsafe { self.free_pool(out as *mut c_void)? };
+
+        Ok(info)
+    }
+
     /// Install an instance of [proto::Protocol] on `handle`
     pub fn install_protocol<'boot, Proto: proto::Protocol<'boot>>(
         &self,
@@ -326,6 +519,23 @@ impl<'table> BootServices<'table> {
         &self,
         handle: EfiHandle,
         interface: *mut Proto::Raw,
+    ) -> Result<()> {
+        self.install_protocol_ptr_with::<Proto>(handle, interface, InterfaceType::NATIVE)
+    }
+
+    /// Install a `Protocol` on `handle`, with the interface type `ty`
+    ///
+    /// [`BootServices::install_protocol_ptr`] uses
+    /// [`InterfaceType::NATIVE`], the only type UEFI currently defines.
+    ///
+    /// # Safety
+    ///
+    /// - See [`BootServices::install_protocol_ptr`]
+    pub unsafe fn install_protocol_ptr_with<'boot, Proto: proto::Protocol<'boot>>(
+        &self,
+        handle: EfiHandle,
+        interface: *mut Proto::Raw,
+        ty: InterfaceType,
     ) -> Result<()> {
         let mut guid = Proto::GUID;
         let mut h = handle;
@@ -334,7 +544,71 @@ impl<'table> BootServices<'table> {
             .install_protocol_interface
             .ok_or(Status::UNSUPPORTED)?;
 
-        (ipi)(&mut h, &mut guid, 0, interface as *mut c_void).into()
+        (ipi)(&mut h, &mut guid, ty, interface as *mut c_void).into()
+    }
+
+    /// Install every `(Guid, interface)` pair in `interfaces` on `handle`
+    ///
+    /// This is atomic, either every protocol is installed or none are.
+    ///
+    /// If `handle` is null, a new handle is created.
+    /// Returns the handle the protocols were installed on.
+    ///
+    /// At most [`MAX_MULTIPLE_INTERFACES`] protocols can be installed at once,
+    /// otherwise [`Status::INVALID_PARAMETER`] is returned.
+    ///
+    /// Returns [`Status::ALREADY_STARTED`] if a device path protocol
+    /// is being installed, and that device path is already installed
+    /// on another handle.
+    ///
+    /// # Safety
+    ///
+    /// - Each interface pointer must be a valid instance of the protocol
+    ///   identified by its [`Guid`]
+    /// - Each interface pointer must live long enough
+    pub unsafe fn install_protocols(
+        &self,
+        handle: EfiHandle,
+        interfaces: &[(Guid, *mut c_void)],
+    ) -> Result<EfiHandle> {
+        let imp = self
+            .interface()
+            .install_multiple_protocol_interfaces
+            .ok_or(Status::UNSUPPORTED)?;
+        let [(g0, i0), (g1, i1), (g2, i2), (g3, i3)] = multiple_args(interfaces)?;
+        let mut h = handle;
+
+        // Safety:
+        // - Unused arguments are null, terminating the list
+        // - Interfaces are the callers responsibility
+        unsafe { (imp)(&mut h, g0, i0, g1, i1, g2, i2, g3, i3, null()) }.map_success(|| h)
+    }
+
+    /// Uninstall every `(Guid, interface)` pair in `interfaces` from `handle`
+    ///
+    /// This is atomic, either every protocol is uninstalled or none are.
+    ///
+    /// At most [`MAX_MULTIPLE_INTERFACES`] protocols can be uninstalled at
+    /// once, otherwise [`Status::INVALID_PARAMETER`] is returned.
+    ///
+    /// # Safety
+    ///
+    /// - Nothing may still be using the uninstalled interfaces
+    pub unsafe fn uninstall_protocols(
+        &self,
+        handle: EfiHandle,
+        interfaces: &[(Guid, *mut c_void)],
+    ) -> Result<()> {
+        let ump = self
+            .interface()
+            .uninstall_multiple_protocol_interfaces
+            .ok_or(Status::UNSUPPORTED)?;
+        let [(g0, i0), (g1, i1), (g2, i2), (g3, i3)] = multiple_args(interfaces)?;
+
+        // Safety:
+        // - Unused arguments are null, terminating the list
+        // - Interfaces are the callers responsibility
+        unsafe { (ump)(handle, g0, i0, g1, i1, g2, i2, g3, i3, null()) }.into()
     }
 
     /// Query `handle` to determine if it supports `Protocol`
@@ -426,15 +700,14 @@ impl<'table> BootServices<'table> {
     /// If the image was from a device, you should set `devpath` to the
     /// [`DevicePath`] for the image on that device.
     ///
-    /// Note that this will return [`Ok`] on a [`Status::SECURITY_VIOLATION`].
-    ///
-    /// You will need to handle that case in [`BootServices::start_image`]
+    /// Note that this will return [`Ok`] on a [`Status::SECURITY_VIOLATION`],
+    /// see [`LoadedImageHandle::security_violation`].
     pub fn load_image(
         &self,
         parent: EfiHandle,
         devpath: Option<&DevicePath>,
         src: &[u8],
-    ) -> Result<EfiHandle> {
+    ) -> Result<LoadedImageHandle> {
         let mut out = EfiHandle::null();
 
         // Safety: Statically correct for this operation
@@ -463,10 +736,13 @@ impl<'table> BootServices<'table> {
     /// If the image was from a device, you should set `devpath` to the
     /// [`DevicePath`] for the image on that device.
     ///
-    /// Note that this will return [`Ok`] on a [`Status::SECURITY_VIOLATION`].
-    ///
-    /// You will need to handle that case in [`BootServices::start_image`]
-    pub fn load_image_fs(&self, parent: EfiHandle, devpath: &DevicePath) -> Result<EfiHandle> {
+    /// Note that this will return [`Ok`] on a [`Status::SECURITY_VIOLATION`],
+    /// see [`LoadedImageHandle::security_violation`].
+    pub fn load_image_fs(
+        &self,
+        parent: EfiHandle,
+        devpath: &DevicePath,
+    ) -> Result<LoadedImageHandle> {
         let mut out = EfiHandle::null();
 
         // Safety: Statically correct for this operation
@@ -532,6 +808,101 @@ impl<'table> BootServices<'table> {
         unsafe { (s)(time) }.into()
     }
 
+    /// Sleep for [`Duration`], keeping the watchdog timer fed
+    ///
+    /// Unlike a single long [`BootServices::stall`], this stalls in
+    /// chunks well below the default 5 minute watchdog timeout,
+    /// resetting the watchdog before each one.
+    ///
+    /// Afterwards the watchdog is set to `restore`, as with
+    /// [`BootServices::set_watchdog`].
+    /// UEFI provides no way to query the current watchdog timeout,
+    /// so callers must pass it themselves.
+    pub fn sleep(&self, dur: Duration, restore: Option<Duration>) -> Result<()> {
+        /// Default UEFI watchdog timeout
+        const WATCHDOG: Duration = Duration::from_secs(5 * 60);
+        /// Longest single stall
+        const CHUNK: Duration = Duration::from_secs(60);
+
+        let mut left = dur;
+        while !left.is_zero() {
+            let chunk = left.min(CHUNK);
+            self.set_watchdog(Some(WATCHDOG))?;
+            self.stall(chunk)?;
+            left -= chunk;
+        }
+        self.set_watchdog(restore)
+    }
+
+    /// Busy-wait for `count` iterations of [`core::hint::spin_loop`]
+    ///
+    /// This is for delays finer than [`BootServices::stall`] can provide.
+    /// See [`BootServices::calibrate`] for how many iterations make up
+    /// a microsecond.
+    #[inline]
+    pub fn spin(&self, count: u64) {
+        for _ in 0..count {
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Estimate how many [`BootServices::spin`] iterations take one
+    /// microsecond
+    ///
+    /// This spins until a firmware timer event fires after 10 milliseconds,
+    /// so it is only as accurate as the firmware timer.
+    /// Results will vary between calls, and can be `0` on very slow systems.
+    pub fn calibrate(&self) -> Result<u64> {
+        /// `EVT_TIMER`
+        const EVT_TIMER: u32 = 0x8000_0000;
+        /// `TimerRelative`
+        const TIMER_RELATIVE: u32 = 2;
+        /// Timer period, in 100ns units
+        const PERIOD: u64 = 100_000;
+        /// Microseconds in [`PERIOD`]
+        const PERIOD_US: u64 = PERIOD / 10;
+        /// Iterations to spin between checks of the timer
+        const BATCH: u64 = 1000;
+
+        let ce = self.interface().create_event.ok_or(Status::UNSUPPORTED)?;
+        let st = self.interface().set_timer.ok_or(Status::UNSUPPORTED)?;
+        let cl = self.interface().close_event.ok_or(Status::UNSUPPORTED)?;
+
+        // Safety: Only used as an out parameter
+        let mut event = unsafe { Event::new(null_mut()) };
+        // Safety: Timer events without a notify function need no context
+        unsafe {
+            (ce)(
+                EVT_TIMER,
+                TaskPriorityLevel::APPLICATION,
+                None,
+                null_mut(),
+                &mut event,
+            )
+        }
+        .map_success(|| ())?;
+
+        // Safety: `create_event` succeeded so `event` is a valid timer event
+        let ret = unsafe { (st)(event, TIMER_RELATIVE, PERIOD) }
+            .map_success(|| ())
+            .and_then(|_| self.spin_until(&event, BATCH));
+
+        // Safety: `event` is a valid event, and not used after this
+        let _ = unsafe { (cl)(event) };
+        ret.map(|count| count / PERIOD_US)
+    }
+
+    /// Spin in batches of `batch` iterations until `event` is signaled,
+    /// returning the total number of iterations
+    fn spin_until(&self, event: &Event, batch: u64) -> Result<u64> {
+        let mut count = 0;
+        while !self.check_event(event)? {
+            self.spin(batch);
+            count += batch;
+        }
+        Ok(count)
+    }
+
     /// The next monotonic count
     pub fn next_monotonic_count(&self) -> Result<u64> {
         let mut out = 0;
@@ -541,15 +912,43 @@ impl<'table> BootServices<'table> {
             .ok_or(Status::UNSUPPORTED)?;
 
         // Safety: Construction ensures safety
-        let ret = unsafe { (gn)(&mut out) };
-        if ret.is_success() {
-            return Ok(out);
-        }
-        Err(ret.into())
+        unsafe { (gn)(&mut out) }.map_success(|| out)
     }
 
     /// Set the watchdog timer. [`None`] disables the timer.
     pub fn set_watchdog(&self, timeout: Option<Duration>) -> Result<()> {
+        self.set_watchdog_full(timeout, 0x10000, None)
+    }
+
+    /// Set the watchdog timer, with a custom watchdog `code` and
+    /// optional descriptive `data` to be logged if it expires.
+    /// [`None`] disables the timer.
+    ///
+    /// Watchdog codes `0x0000` to `0xFFFF` are reserved for firmware.
+    ///
+    /// Returns [`Status::INVALID_PARAMETER`] if `code` is reserved,
+    /// or `data` has any internal nulls.
+    pub fn set_watchdog_full(
+        &self,
+        timeout: Option<Duration>,
+        code: u64,
+        data: Option<&str>,
+    ) -> Result<()> {
+        if code <= 0xFFFF {
+            return Err(Status::INVALID_PARAMETER.into());
+        }
+        let mut data: Vec<u16> = match data {
+            Some(s) if s.contains('\0') => return Err(Status::INVALID_PARAMETER.into()),
+            Some(s) => encode_ucs2(s),
+            None => Vec::new(),
+        };
+        // Size is in bytes
+        let (size, data_ptr) = if data.is_empty() {
+            (0, null_mut())
+        } else {
+            (data.len() * size_of::<u16>(), data.as_mut_ptr())
+        };
+
         let timeout = timeout.unwrap_or_default();
         let swt = self
             .interface()
@@ -565,7 +964,9 @@ impl<'table> BootServices<'table> {
             Err(e) => return e.into(),
         };
         // Safety: Construction ensures safety. Statically verified arguments.
-        unsafe { (swt)(secs, 0x10000, 0, null_mut()) }.into()
+        // `data_ptr` is either null or a nul terminated string valid for `size`
+        // bytes
+        unsafe { (swt)(secs, code, size, data_ptr) }.into()
     }
 }
 
@@ -591,14 +992,27 @@ impl<'table> BootServices<'table> {
         let ret = unsafe { (ap)(ty, size, &mut out) };
 
         if ret.is_success() {
-            assert!(!out.is_null(), "UEFI Allocator returned successful null");
-            // Safety: assert
-            unsafe { Ok(NonNull::new_unchecked(out)) }
+            // A successful null is a firmware bug, but must not panic here,
+            // as this backs the global allocator.
+            NonNull::new(out).ok_or_else(|| Status::DEVICE_ERROR.into())
         } else {
             Err(ret.into())
         }
     }
 
+    /// Allocate `size` bytes of zeroed memory from pool of type `ty`.
+    /// Allocations are 8 byte aligned.
+    ///
+    /// UEFI does not guarantee pool memory is zeroed,
+    /// see [`BootServices::allocate_pool`].
+    #[inline]
+    pub fn allocate_pool_zeroed(&self, ty: MemoryType, size: usize) -> Result<NonNull<c_void>> {
+        let ptr = self.allocate_pool(ty, size)?;
+        // Safety: `ptr` was just allocated and is valid for `size` bytes
+        unsafe { ptr.as_ptr().cast::<u8>().write_bytes(0, size) };
+        Ok(ptr)
+    }
+
     /// Allocate [`size_of::<T>()`] bytes from pool of type `ty`.
     /// Allocations are 8 byte aligned.
     ///
@@ -614,6 +1028,21 @@ impl<'table> BootServices<'table> {
         self.allocate_pool(ty, size_of::<T>()).map(|n| n.cast())
     }
 
+    /// The same as [`allocate_pool_ty`][alloc_ty], but the memory is zeroed.
+    ///
+    /// See [`BootServices::allocate_pool_zeroed`]
+    ///
+    /// # Safety
+    ///
+    /// See [`allocate_pool_ty`][alloc_ty]
+    ///
+    /// [alloc_ty]: BootServices::allocate_pool_ty
+    #[inline]
+    pub unsafe fn allocate_pool_ty_zeroed<T>(&self, ty: MemoryType) -> Result<NonNull<T>> {
+        self.allocate_pool_zeroed(ty, size_of::<T>())
+            .map(|n| n.cast())
+    }
+
     /// The same as [`allocate_pool_ty`][alloc_ty], but allocates `len`
     /// *elements* of `T`.
     ///
@@ -632,6 +1061,110 @@ impl<'table> BootServices<'table> {
             .map(|n| n.cast())
     }
 
+    /// Get the current UEFI memory map
+    ///
+    /// The returned [`MemoryMap`] owns its buffer and may be kept
+    /// across ExitBootServices.
+    pub fn memory_map(&self) -> Result<MemoryMap> {
+        let gm = self.interface().get_memory_map.ok_or(Status::UNSUPPORTED)?;
+        let mut size = 0;
+        let mut key = 0;
+        let mut entry_size = 0;
+        let mut version = 0;
+
+        // Safety: A zero size and null buffer is how we get the size
+        let ret = unsafe {
+            (gm)(
+                &mut size,
+                null_mut(),
+                &mut key,
+                &mut entry_size,
+                &mut version,
+            )
+        };
+        if ret != Status::BUFFER_TOO_SMALL {
+            return Err(ret.into());
+        }
+        if entry_size < size_of::<MemoryDescriptor>() {
+            return Err(Status::INCOMPATIBLE_VERSION.into());
+        }
+
+        loop {
+            // Allocating the buffer can itself change the memory map
+            size += entry_size * 2;
+            let buf = self.allocate_pool(MemoryType::LOADER_DATA, size)?;
+            let mut out_size = size;
+
+            // Safety: `buf` is valid for `size` bytes
+            let ret = unsafe {
+                (gm)(
+                    &mut out_size,
+                    buf.as_ptr().cast(),
+                    &mut key,
+                    &mut entry_size,
+                    &mut version,
+                )
+            };
+            if ret.is_success() {
+                // Safety:
+                // - `buf` is from `allocate_pool`
+                // - Firmware wrote a map of `out_size` bytes
+                // - `entry_size` checked above
+                return Ok(unsafe { MemoryMap::new(buf, out_size, entry_size, key, version) });
+            }
+
+            // Safety: `buf` is from `allocate_pool`
+            unsafe { self.free_pool(buf.as_ptr()) }?;
+            if ret != Status::BUFFER_TOO_SMALL {
+                return Err(ret.into());
+            }
+            size = out_size;
+        }
+    }
+
+    /// Get the current UEFI memory map into `buf`,
+    /// returning the map key and the size of each entry in bytes.
+    ///
+    /// `buf` is reused, and only grown when firmware reports it is too small,
+    /// which makes this suitable for fetching the map repeatedly,
+    /// such as when retrying ExitBootServices.
+    ///
+    /// On success, the length of `buf` is the size of the map in bytes.
+    /// Entries may not be aligned, and the entry size may be larger than
+    /// [`size_of::<MemoryDescriptor>()`].
+    pub fn memory_map_into(&self, buf: &mut Vec<u8>) -> Result<(usize, usize)> {
+        let gm = self.interface().get_memory_map.ok_or(Status::UNSUPPORTED)?;
+        let mut key = 0;
+        let mut entry_size = 0;
+        let mut version = 0;
+
+        loop {
+            buf.clear();
+            let mut size = buf.capacity();
+
+            // Safety: `buf` is valid for `size` bytes
+            let ret = unsafe {
+                (gm)(
+                    &mut size,
+                    buf.as_mut_ptr().cast(),
+                    &mut key,
+                    &mut entry_size,
+                    &mut version,
+                )
+            };
+            if ret.is_success() {
+                // Safety: Firmware initialized `size` bytes of `buf`
+                unsafe { buf.set_len(size) };
+                return Ok((key, entry_size));
+            }
+            if ret != Status::BUFFER_TOO_SMALL {
+                return Err(ret.into());
+            }
+            // Growing the buffer can itself change the memory map
+            buf.reserve(size + entry_size * 2);
+        }
+    }
+
     /// Free memory allocated by [BootServices::allocate_pool]
     ///
     /// # Safety
@@ -645,8 +1178,112 @@ impl<'table> BootServices<'table> {
     }
 }
 
+/// Misc
+impl<'table> BootServices<'table> {
+    /// Compute the CRC32 of `data` using the firmware implementation
+    pub fn calculate_crc32(&self, data: &[u8]) -> Result<u32> {
+        let cc = self
+            .interface()
+            .calculate_crc32
+            .ok_or(Status::UNSUPPORTED)?;
+        let mut crc = 0;
+
+        // Safety: `data` is valid for `data.len()` bytes
+        unsafe { (cc)(data.as_ptr().cast(), data.len(), &mut crc) }.map_success(|| crc)
+    }
+}
+
 /// Event/Timer/Task Priority
-impl<'table> BootServices<'table> {}
+impl<'table> BootServices<'table> {
+    /// Signal `event`
+    ///
+    /// If `event` has a notify function, it is queued to run.
+    /// Signaling an already signaled event has no effect.
+    pub fn signal_event(&self, event: &Event) -> Result<()> {
+        let se = self.interface().signal_event.ok_or(Status::UNSUPPORTED)?;
+
+        // Safety: Construction ensures safety. Statically verified arguments.
+        unsafe { (se)(*event) }.into()
+    }
+
+    /// Check whether `event` is signaled, without blocking
+    ///
+    /// Returns `Ok(true)` if it was signaled, which also clears it,
+    /// or `Ok(false)` if not.
+    ///
+    /// Returns [`Status::INVALID_PARAMETER`] if `event` is of type
+    /// `EVT_NOTIFY_SIGNAL`
+    pub fn check_event(&self, event: &Event) -> Result<bool> {
+        let ce = self.interface().check_event.ok_or(Status::UNSUPPORTED)?;
+
+        // Safety: Construction ensures safety. Statically verified arguments.
+        let ret = unsafe { (ce)(*event) };
+        if ret.is_success() {
+            Ok(true)
+        } else if ret == Status::NOT_READY {
+            Ok(false)
+        } else {
+            Err(ret.into())
+        }
+    }
+}
+
+/// An image loaded by [`BootServices::load_image`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadedImageHandle {
+    /// Handle to the loaded image
+    pub handle: EfiHandle,
+
+    /// Whether the image failed Secure Boot verification,
+    /// [`Status::SECURITY_VIOLATION`]
+    ///
+    /// The image was still loaded, but [`BootServices::start_image`] will
+    /// refuse to start it.
+    /// Callers should usually [unload][`BootServices::unload_image`] it.
+    pub security_violation: bool,
+}
+
+/// Information about an agent that has a protocol open
+///
+/// See [`BootServices::open_protocol_information`]
+#[derive(Debug, Clone, Copy)]
+pub struct OpenInfo {
+    info: RawOpenInfo,
+}
+
+impl OpenInfo {
+    fn new(info: RawOpenInfo) -> Self {
+        Self { info }
+    }
+
+    /// The agent that opened the protocol
+    pub fn agent(&self) -> EfiHandle {
+        self.info.agent_handle
+    }
+
+    /// The controller the protocol was opened for, if any
+    pub fn controller(&self) -> Option<EfiHandle> {
+        let c = self.info.controller_handle;
+        if !c.as_ptr().is_null() {
+            Some(c)
+        } else {
+            None
+        }
+    }
+
+    /// Raw attributes the protocol was opened with
+    ///
+    /// For example, `0x20` for exclusive, as [`BootServices::open_protocol`]
+    /// does.
+    pub fn attributes(&self) -> u32 {
+        self.info.attributes
+    }
+
+    /// How many times the protocol has been opened by this agent
+    pub fn open_count(&self) -> u32 {
+        self.info.open_count
+    }
+}
 
 interface!(
     /// The UEFI Runtime Services
@@ -713,7 +1350,6 @@ pub(crate) struct Internal;
 //
 // In the [`Runtime`] state, it is only valid to use [`RuntimeServices`] and
 // the fields specified by [`RawSystemTable`]
-#[derive(Debug)]
 #[repr(transparent)]
 pub struct SystemTable<State> {
     /// Pointer to the table.
@@ -729,6 +1365,26 @@ pub struct SystemTable<State> {
     phantom: PhantomData<*const State>,
 }
 
+/// Only prints fields that are valid in every state.
+///
+/// The service and protocol pointers are printed as addresses,
+/// and never dereferenced.
+impl<T> fmt::Debug for SystemTable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let t = self.table();
+        f.debug_struct("SystemTable")
+            .field("table", &self.table)
+            .field("header", &t.header)
+            .field("firmware_vendor", &t.firmware_vendor)
+            .field("firmware_revision", &t.firmware_revision)
+            .field("boot_services", &t.boot_services)
+            .field("runtime_services", &t.runtime_services)
+            .field("number_of_table_entries", &t.number_of_table_entries)
+            .field("configuration_table", &t.configuration_table)
+            .finish_non_exhaustive()
+    }
+}
+
 // Internal, all
 impl<T> SystemTable<T> {
     /// Create new SystemTable
@@ -770,6 +1426,35 @@ impl SystemTable<Internal> {
             None
         }
     }
+
+    /// Get the SystemTable if in runtime mode.
+    ///
+    /// If ExitBootServices HAS been called,
+    /// return [`SystemTable<Runtime>`], otherwise [`None`]
+    pub(crate) fn as_runtime(&self) -> Option<SystemTable<Runtime>> {
+        let t = self.table();
+        if t.boot_services.is_null() && !t.runtime_services.is_null() {
+            // Safety:
+            // - Above check verifies ExitBootServices has been called,
+            //   and the Runtime Services table exists.
+            Some(unsafe { SystemTable::new(self.table) })
+        } else {
+            None
+        }
+    }
+}
+
+/// Available in every state
+impl<T> SystemTable<T> {
+    /// Reference to the UEFI Runtime services.
+    ///
+    /// This is only valid for as long as the SystemTable is
+    pub fn runtime(&self) -> RuntimeServices<'_> {
+        let ptr = self.table().runtime_services;
+        assert!(!ptr.is_null(), "runtime_services handle was null");
+        // Safety: Construction ensures safety.
+        unsafe { RuntimeServices::new(ptr) }
+    }
 }
 
 /// Available during Boot Services
@@ -785,8 +1470,10 @@ impl SystemTable<Boot> {
     }
 
     /// Firmware-specific value indicating its revision
-    pub fn firmware_revision(&self) -> u32 {
-        self.table().firmware_revision
+    ///
+    /// See [`FirmwareRevision`] for details
+    pub fn firmware_revision(&self) -> FirmwareRevision {
+        FirmwareRevision::new(self.table().firmware_revision)
     }
 
     /// Returns the (Major, Minor) UEFI Revision that this implementation claims
@@ -798,6 +1485,55 @@ impl SystemTable<Boot> {
         )
     }
 
+    /// Re-validate the signatures, sizes, and CRCs of the System, Boot, and
+    /// Runtime tables, as was done on entry.
+    ///
+    /// Useful before a sensitive operation, if you suspect the tables
+    /// have been corrupted.
+    pub fn revalidate(&self) -> Result<()> {
+        // Safety:
+        // - `Self::new` verifies this pointer is valid
+        // - Still in boot mode, so the pointers in the table are valid
+        unsafe { RawSystemTable::validate(self.table) }
+    }
+
+    /// Like [`SystemTable::revalidate`], but also cross-check our
+    /// software CRC against [`BootServices::calculate_crc32`] for each table.
+    ///
+    /// A mismatch is logged as a warning, and does not cause an error,
+    /// as it indicates a bug in either nuefi or firmware.
+    pub fn validate_with_firmware(&self) -> Result<()> {
+        self.revalidate()?;
+        let boot = self.boot();
+        let t = self.table();
+        let tables: [(&str, *const Header); 3] = [
+            ("SystemTable", self.table as *const Header),
+            ("BootServices", t.boot_services as *const Header),
+            ("RuntimeServices", t.runtime_services as *const Header),
+        ];
+        for (name, table) in tables {
+            // Safety: `revalidate` verified these tables, and that they are
+            // valid for `size` bytes
+            let bytes = unsafe {
+                let size = (*table).size as usize;
+                from_raw_parts(table as *const u8, size)
+            };
+            let mut bytes = bytes.to_vec();
+            // The CRC is computed with the CRC field zeroed
+            bytes[HEADER_CRC_OFFSET..HEADER_CRC_OFFSET + size_of::<u32>()].fill(0);
+
+            let ours = CRC.checksum(&bytes);
+            let theirs = boot.calculate_crc32(&bytes)?;
+            if ours != theirs {
+                log::warn!(
+                    "{name} CRC mismatch: nuefi calculated {ours:#010X}, \
+                    firmware calculated {theirs:#010X}"
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Output on stdout.
     ///
     /// This is only valid for as long as the SystemTable is
@@ -828,6 +1564,42 @@ impl SystemTable<Boot> {
         unsafe { BootServices::new(ptr) }
     }
 
+    /// Register `f` to be called when ExitBootServices is called
+    ///
+    /// This creates an `EVT_SIGNAL_EXIT_BOOT_SERVICES` event,
+    /// which is the spec-sanctioned place to release boot services
+    /// resources at the last moment.
+    ///
+    /// The event lives for the rest of boot services, and cannot be removed.
+    ///
+    /// # Note
+    ///
+    /// `f` runs inside ExitBootServices, at [`TaskPriorityLevel::NOTIFY`].
+    /// It must not allocate or free memory, use the global allocator,
+    /// or use any event or timer services, as doing so may change the
+    /// memory map and cause ExitBootServices to fail.
+    pub fn on_exit_boot_services(&self, f: fn()) -> Result<()> {
+        /// `EVT_SIGNAL_EXIT_BOOT_SERVICES`
+        const EVT_SIGNAL_EXIT_BOOT_SERVICES: u32 = 0x0000_0201;
+
+        let boot = self.boot();
+        let ce = boot.interface().create_event.ok_or(Status::UNSUPPORTED)?;
+
+        // Safety: Only used as an out parameter
+        let mut event = unsafe { Event::new(null_mut()) };
+        // Safety: `exit_notify` expects `context` to be a `fn()`, which it is
+        unsafe {
+            (ce)(
+                EVT_SIGNAL_EXIT_BOOT_SERVICES,
+                TaskPriorityLevel::NOTIFY,
+                Some(exit_notify),
+                f as *mut c_void,
+                &mut event,
+            )
+        }
+        .into()
+    }
+
     /// Iterator over UEFI Configuration tables
     ///
     /// See [`config`] and [`config::GenericConfig`] for details
@@ -856,3 +1628,73 @@ impl SystemTable<Boot> {
             .and_then(|t| t.as_table::<T>())
     }
 }
+
+/// Notify function for [`SystemTable::on_exit_boot_services`]
+///
+/// # Safety
+///
+/// - `context` must be a `fn()`
+unsafe extern "efiapi" fn exit_notify(_event: Event, context: *mut c_void) {
+    let f: fn() = transmute(context);
+    f();
+}
+
+/// Arguments for the variadic multiple protocol functions
+///
+/// Unused pairs are null
+fn multiple_args(
+    interfaces: &[(Guid, *mut c_void)],
+) -> Result<[(*const Guid, *mut c_void); MAX_MULTIPLE_INTERFACES]> {
+    if interfaces.len() > MAX_MULTIPLE_INTERFACES {
+        return Err(Status::INVALID_PARAMETER.into());
+    }
+    let mut args = [(null(), null_mut()); MAX_MULTIPLE_INTERFACES];
+    for (arg, (guid, interface)) in args.iter_mut().zip(interfaces) {
+        *arg = (guid as *const Guid, *interface);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicPtr, Ordering};
+
+    use super::*;
+    use crate::tests::mock_boot;
+
+    /// The `search_key` last passed to [`locate_handle`]
+    static KEY: AtomicPtr<c_void> = AtomicPtr::new(null_mut());
+
+    /// Returns two handles, recording `search_key` in [`KEY`]
+    unsafe extern "efiapi" fn locate_handle(
+        _search: LocateSearch,
+        _protocol: *const Guid,
+        search_key: *const c_void,
+        size: *mut usize,
+        buffer: *mut EfiHandle,
+    ) -> Status {
+        KEY.store(search_key.cast_mut(), Ordering::Relaxed);
+        let need = size_of::<EfiHandle>() * 2;
+        if *size < need {
+            *size = need;
+            return Status::BUFFER_TOO_SMALL;
+        }
+        *size = need;
+        for i in 0..2 {
+            buffer.add(i).write(EfiHandle::new((i + 1) as *mut c_void));
+        }
+        Status::SUCCESS
+    }
+
+    #[test]
+    fn all_handles_ignores_key() {
+        let mut raw = mock_boot(|b| b.locate_handle = Some(locate_handle));
+        // Safety: `raw` is valid and outlives `boot`
+        let boot = unsafe { BootServices::new(&mut raw) };
+
+        KEY.store(NonNull::dangling().as_ptr(), Ordering::Relaxed);
+        let handles = boot.all_handles().unwrap();
+        assert_eq!(handles.len(), 2);
+        assert!(KEY.load(Ordering::Relaxed).is_null());
+    }
+}
@@ -5,7 +5,9 @@
 ///
 /// Uses a phantom lifetime `'table` to ensure it won't outlive the System Table
 ///
-/// All interfaces derive [`Debug`]
+/// All interfaces derive [`Debug`].
+/// For Protocols, the `Protocol` macro replaces this with an impl that also
+/// prints the protocol name and GUID.
 ///
 /// # Safety
 ///